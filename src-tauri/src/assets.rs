@@ -0,0 +1,262 @@
+use crate::blog::{blog_dir, slugify};
+use crate::config::{ensure_manager_dir, load_config, manager_dir, AssetLocation};
+use crate::error::AppError;
+use crate::images::{import_image, is_raster};
+use crate::transaction::Transaction;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp", "avif", "svg"];
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PostAsset {
+    pub name: String,
+    /// Path relative to the repo root
+    pub path: String,
+    /// How the post refers to the file
+    pub url: String,
+    pub location: AssetLocation,
+    pub size: u64,
+    pub is_image: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportedAsset {
+    /// The imported file (for images without a kept original, the fallback variant)
+    pub asset: PostAsset,
+    /// Resized/converted copies generated for responsive images
+    pub variants: Vec<PostAsset>,
+    /// Markdown (or `<picture>` HTML) to insert into the post body
+    pub snippet: String,
+}
+
+/// Directory holding a post's assets for the given location
+pub(crate) fn asset_dir(repo_path: &str, slug: &str, location: AssetLocation) -> PathBuf {
+    match location {
+        AssetLocation::Static => Path::new(repo_path).join("static").join("blog").join(slug),
+        AssetLocation::Post => blog_dir(repo_path).join(slug),
+    }
+}
+
+/// URL the post body uses for an asset file
+pub(crate) fn asset_url(slug: &str, name: &str, location: AssetLocation) -> String {
+    match location {
+        AssetLocation::Static => format!("/blog/{}/{}", slug, name),
+        AssetLocation::Post => format!("./{}", name),
+    }
+}
+
+pub(crate) fn is_image(name: &str) -> bool {
+    Path::new(name)
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| IMAGE_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+}
+
+pub(crate) fn hash_bytes(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Slugified stem plus lowercase extension, e.g. `IMG 2041 (1).JPG` -> `img-2041-1.jpg`
+pub(crate) fn normalize_file_name(source: &Path) -> String {
+    let stem = source
+        .file_stem()
+        .map(|s| slugify(s.to_string_lossy().to_string()))
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| "asset".to_string());
+
+    match source.extension() {
+        Some(ext) => format!("{}.{}", stem, ext.to_string_lossy().to_lowercase()),
+        None => stem,
+    }
+}
+
+/// First name based on `name` that is not already taken in `dir`
+pub(crate) fn unique_file_name(dir: &Path, name: &str) -> String {
+    if !dir.join(name).exists() {
+        return name.to_string();
+    }
+
+    let path = Path::new(name);
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let ext = path.extension().map(|e| format!(".{}", e.to_string_lossy())).unwrap_or_default();
+
+    (2..)
+        .map(|n| format!("{}-{}{}", stem, n, ext))
+        .find(|candidate| !dir.join(candidate).exists())
+        .unwrap()
+}
+
+/// Markdown snippet referencing an asset: an image embed or a plain link
+pub(crate) fn markdown_snippet(name: &str, url: &str) -> String {
+    let stem = Path::new(name).file_stem().unwrap_or_default().to_string_lossy();
+    let label = stem.replace('-', " ");
+
+    if is_image(name) {
+        format!("![{}]({})", label, url)
+    } else {
+        format!("[{}]({})", label, url)
+    }
+}
+
+pub(crate) fn describe_asset(repo_path: &str, slug: &str, path: &Path, location: AssetLocation) -> Option<PostAsset> {
+    let size = fs::metadata(path).ok()?.len();
+    asset_entry(repo_path, slug, path, location, size)
+}
+
+/// Describe an asset that may not have been written yet
+pub(crate) fn asset_entry(repo_path: &str, slug: &str, path: &Path, location: AssetLocation, size: u64) -> Option<PostAsset> {
+    let name = path.file_name()?.to_string_lossy().to_string();
+
+    Some(PostAsset {
+        url: asset_url(slug, &name, location),
+        path: path
+            .strip_prefix(repo_path)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/"),
+        is_image: is_image(&name),
+        name,
+        location,
+        size,
+    })
+}
+
+fn assets_in(repo_path: &str, slug: &str, location: AssetLocation) -> Result<Vec<PostAsset>, AppError> {
+    let dir = asset_dir(repo_path, slug, location);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut assets = Vec::new();
+    for entry in fs::read_dir(&dir).map_err(|e| AppError::io("list assets", &dir, e))? {
+        let path = entry.map_err(|e| AppError::io("list assets", &dir, e))?.path();
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+
+        // The post directory also holds the page itself
+        if !path.is_file() || name.starts_with('+') || name.starts_with('.') {
+            continue;
+        }
+        assets.extend(describe_asset(repo_path, slug, &path, location));
+    }
+
+    assets.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(assets)
+}
+
+/// Source hashes of processed imports, whose stored files differ from the
+/// source (resized, converted or metadata-stripped), mapped to the file name
+pub(crate) fn manifest_path(repo_path: &str, slug: &str) -> PathBuf {
+    manager_dir(repo_path).join("assets").join(format!("{}.json", slug))
+}
+
+fn load_manifest(repo_path: &str, slug: &str) -> BTreeMap<String, String> {
+    fs::read_to_string(manifest_path(repo_path, slug))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn record_import(transaction: &mut Transaction, repo_path: &str, slug: &str, hash: String, name: &str) -> Result<(), AppError> {
+    let mut manifest = load_manifest(repo_path, slug);
+    manifest.insert(hash, name.to_string());
+
+    let content = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| AppError::Other {
+            message: format!("Failed to serialize asset manifest: {}", e),
+        })?;
+    ensure_manager_dir(repo_path)?;
+    transaction.write(manifest_path(repo_path, slug), content);
+    Ok(())
+}
+
+/// Reject `bytes` if the same content was already imported for the post,
+/// either as a stored file or as the source of a processed image
+fn ensure_not_duplicate(repo_path: &str, slug: &str, dir: &Path, bytes: &[u8]) -> Result<(), AppError> {
+    let hash = hash_bytes(bytes);
+
+    if let Some(name) = load_manifest(repo_path, slug).get(&hash) {
+        if dir.join(name).exists() {
+            return Err(AppError::invalid(format!("Asset already imported as {}", name)));
+        }
+    }
+
+    if !dir.exists() {
+        return Ok(());
+    }
+
+    for entry in fs::read_dir(dir).map_err(|e| AppError::io("list assets", dir, e))? {
+        let path = entry.map_err(|e| AppError::io("list assets", dir, e))?.path();
+        if !path.is_file() {
+            continue;
+        }
+        let existing = fs::read(&path).map_err(|e| AppError::io("read asset", &path, e))?;
+        if hash_bytes(&existing) == hash {
+            return Err(AppError::invalid(format!(
+                "Asset already imported as {}",
+                path.file_name().unwrap_or_default().to_string_lossy()
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Copy a file into a post's asset directory and return the Markdown to
+/// insert. Files identical to an existing asset are rejected.
+#[tauri::command]
+pub fn import_asset(repo_path: String, slug: String, source_path: String) -> Result<ImportedAsset, AppError> {
+    let post_file = blog_dir(&repo_path).join(&slug).join("+page.md");
+    if !post_file.exists() {
+        return Err(AppError::PostNotFound { slug, path: post_file });
+    }
+
+    let source = Path::new(&source_path);
+    if !source.is_file() {
+        return Err(AppError::invalid(format!("Not a file: {}", source_path)));
+    }
+
+    let config = load_config(&repo_path)?.assets;
+    let location = config.location;
+    let dir = asset_dir(&repo_path, &slug, location);
+
+    let bytes = fs::read(source).map_err(|e| AppError::io("read asset", source, e))?;
+    ensure_not_duplicate(&repo_path, &slug, &dir, &bytes)?;
+
+    // Variants and the manifest are written together, or not at all
+    let mut transaction = Transaction::default();
+
+    let name = normalize_file_name(source);
+    if config.images.responsive && is_raster(&name) {
+        let imported = import_image(&mut transaction, &repo_path, &slug, &bytes, &name, location, &config.images)?;
+        record_import(&mut transaction, &repo_path, &slug, hash_bytes(&bytes), &imported.asset.name)?;
+        transaction.commit()?;
+        return Ok(imported);
+    }
+
+    let name = unique_file_name(&dir, &name);
+    let target = dir.join(&name);
+    let asset = asset_entry(&repo_path, &slug, &target, location, bytes.len() as u64)
+        .ok_or_else(|| AppError::invalid(format!("Not a valid asset name: {}", name)))?;
+    transaction.write(target, bytes);
+    transaction.commit()?;
+
+    Ok(ImportedAsset {
+        snippet: markdown_snippet(&asset.name, &asset.url),
+        asset,
+        variants: Vec::new(),
+    })
+}
+
+/// List the files imported for a post, from both asset locations
+#[tauri::command]
+pub fn list_post_assets(repo_path: String, slug: String) -> Result<Vec<PostAsset>, AppError> {
+    let mut assets = assets_in(&repo_path, &slug, AssetLocation::Static)?;
+    assets.extend(assets_in(&repo_path, &slug, AssetLocation::Post)?);
+    Ok(assets)
+}
@@ -1,424 +1,1305 @@
-use serde::{Deserialize, Serialize};
-use std::fs;
-use std::path::Path;
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct PostMetadata {
-    pub title: String,
-    pub slug: String,
-    pub excerpt: String,
-    pub content: String,
-    pub tags: Vec<String>,
-    pub date: String,
-    pub keywords: String,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ExistingPost {
-    pub slug: String,
-    pub title: String,
-    pub date: String,
-    pub excerpt: String,
-    pub tags: Vec<String>,
-}
-
-/// Generate the post template (Svelte + Markdown)
-fn generate_post_template(metadata: &PostMetadata) -> String {
-    let tags_str = metadata.tags.iter()
-        .map(|t| format!("\"{}\"", t))
-        .collect::<Vec<_>>()
-        .join(", ");
-    
-    // Use provided content or default placeholder
-    let post_content = if metadata.content.is_empty() {
-        format!("# {}\n\n<!-- Write your content here -->", metadata.title)
-    } else {
-        metadata.content.clone()
-    };
-
-    format!(
-        r#"<script context="module">
-    export const metadata = {{
-        title: "{}",
-        date: "{}",
-        excerpt: "{}",
-        tags: [{}]
-    }};
-</script>
-
-<script lang="ts">
-    import Citation from '$lib/components/Citation.svelte';
-    import References from '$lib/components/References.svelte';
-    
-    function formatDate(dateString: string): string {{
-        return new Date(dateString).toLocaleDateString('en-US', {{
-            year: 'numeric', month: 'long', day: 'numeric'
-        }});
-    }}
-</script>
-
-<svelte:head>
-    <title>{{metadata.title}} | Mr. Wong's Blog</title>
-    <meta name="description" content={{metadata.excerpt}} />
-</svelte:head>
-
-<article class="blog-post">
-    <div class="container">
-        <header class="post-header">
-            <a href="/blog" class="back-link">
-                <svg width="20" height="20" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2">
-                    <path d="M19 12H5M12 19l-7-7 7-7" />
-                </svg>
-                Back to Blog
-            </a>
-            <div class="post-meta">
-                <time datetime={{metadata.date}}>{{formatDate(metadata.date)}}</time>
-                <div class="post-tags">
-                    {{#each metadata.tags as tag}}
-                        <span class="tag">{{tag}}</span>
-                    {{/each}}
-                </div>
-            </div>
-            <h1 class="post-title">{{metadata.title}}</h1>
-        </header>
-        
-        <div class="post-content">
-
-{}
-
-        </div>
-        
-        <footer class="post-footer">
-            <a href="/blog" class="back-link">
-                <svg width="20" height="20" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2">
-                    <path d="M19 12H5M12 19l-7-7 7-7" />
-                </svg>
-                Back to Blog
-            </a>
-        </footer>
-    </div>
-</article>
-
-<style>
-    .blog-post {{ min-height: calc(100vh - 72px); padding: var(--space-4xl) 0; background: var(--color-bg-primary); }}
-    .blog-post .container {{ max-width: 800px; }}
-    .back-link {{ display: inline-flex; align-items: center; gap: var(--space-sm); color: var(--color-text-secondary); font-size: var(--font-size-sm); font-weight: 500; margin-bottom: var(--space-xl); transition: color var(--transition-fast); }}
-    .back-link:hover {{ color: var(--color-accent); }}
-    .post-header {{ margin-bottom: var(--space-2xl); }}
-    .post-meta {{ display: flex; align-items: center; gap: var(--space-md); margin-bottom: var(--space-lg); flex-wrap: wrap; }}
-    .post-meta time {{ font-size: var(--font-size-sm); color: var(--color-text-muted); }}
-    .post-tags {{ display: flex; gap: var(--space-xs); flex-wrap: wrap; }}
-    .tag {{ font-size: var(--font-size-xs); padding: var(--space-xs) var(--space-sm); background: rgba(59, 130, 246, 0.15); color: var(--color-accent-light); border-radius: var(--radius-sm); font-weight: 500; }}
-    .post-title {{ font-size: var(--font-size-4xl); font-weight: 700; line-height: 1.2; color: var(--color-text-primary); }}
-    .post-content {{ color: var(--color-text-secondary); line-height: 1.8; font-size: var(--font-size-lg); }}
-    .post-content :global(h1), .post-content :global(h2), .post-content :global(h3) {{ color: var(--color-text-primary); margin-top: var(--space-2xl); margin-bottom: var(--space-md); font-weight: 600; }}
-    .post-content :global(h1) {{ font-size: var(--font-size-3xl); }}
-    .post-content :global(h2) {{ font-size: var(--font-size-2xl); }}
-    .post-content :global(h3) {{ font-size: var(--font-size-xl); }}
-    .post-content :global(p) {{ margin-bottom: var(--space-lg); }}
-    .post-content :global(a) {{ color: var(--color-accent-light); text-decoration: underline; }}
-    .post-content :global(ul), .post-content :global(ol) {{ margin-bottom: var(--space-lg); padding-left: var(--space-xl); }}
-    .post-content :global(li) {{ margin-bottom: var(--space-sm); }}
-    .post-content :global(blockquote) {{ border-left: 3px solid var(--color-accent); background: rgba(59, 130, 246, 0.1); padding: var(--space-md) var(--space-lg); margin: var(--space-lg) 0; border-radius: 0 var(--radius-lg) var(--radius-lg) 0; font-style: italic; }}
-    .post-content :global(strong) {{ color: var(--color-text-primary); font-weight: 600; }}
-    .post-content :global(em) {{ font-style: italic; }}
-    .post-content :global(hr) {{ border: none; border-top: 1px solid var(--color-border); margin: var(--space-2xl) 0; }}
-    .post-footer {{ margin-top: var(--space-4xl); padding-top: var(--space-xl); border-top: 1px solid var(--color-border); }}
-</style>
-"#,
-        metadata.title,
-        metadata.date,
-        metadata.excerpt,
-        tags_str,
-        post_content
-    )
-}
-
-/// Generate the listing entry to add to +page.svelte
-fn generate_listing_entry(metadata: &PostMetadata) -> String {
-    let tags_str = metadata.tags.iter()
-        .map(|t| format!("\"{}\"", t))
-        .collect::<Vec<_>>()
-        .join(", ");
-
-    format!(
-        r#"        {{
-            slug: "{}",
-            title: "{}",
-            date: "{}",
-            excerpt: "{}",
-            tags: [{}],
-            content: "{}",
-        }},"#,
-        metadata.slug,
-        metadata.title,
-        metadata.date,
-        metadata.excerpt,
-        tags_str,
-        metadata.keywords
-    )
-}
-
-#[tauri::command]
-pub fn create_blog_post(
-    repo_path: String,
-    title: String,
-    slug: String,
-    excerpt: String,
-    content: String,
-    tags: Vec<String>,
-    keywords: String,
-) -> Result<String, String> {
-    let date = chrono::Local::now().format("%Y-%m-%d").to_string();
-    
-    let metadata = PostMetadata {
-        title: title.clone(),
-        slug: slug.clone(),
-        excerpt,
-        content,
-        tags,
-        date,
-        keywords,
-    };
-
-    // Create post directory
-    let post_dir = Path::new(&repo_path)
-        .join("src")
-        .join("routes")
-        .join("blog")
-        .join(&slug);
-
-    if post_dir.exists() {
-        return Err(format!("Post directory already exists: {}", slug));
-    }
-
-    fs::create_dir_all(&post_dir)
-        .map_err(|e| format!("Failed to create directory: {}", e))?;
-
-    // Write post file
-    let post_content = generate_post_template(&metadata);
-    let post_file = post_dir.join("+page.md");
-    fs::write(&post_file, post_content)
-        .map_err(|e| format!("Failed to write post file: {}", e))?;
-
-    // Update blog listing
-    let listing_path = Path::new(&repo_path)
-        .join("src")
-        .join("routes")
-        .join("blog")
-        .join("+page.svelte");
-
-    let listing_content = fs::read_to_string(&listing_path)
-        .map_err(|e| format!("Failed to read blog listing: {}", e))?;
-
-    let new_entry = generate_listing_entry(&metadata);
-    let insert_marker = "const posts = [";
-    
-    if let Some(pos) = listing_content.find(insert_marker) {
-        let insert_pos = pos + insert_marker.len();
-        let new_content = format!(
-            "{}\n{}{}",
-            &listing_content[..insert_pos],
-            new_entry,
-            &listing_content[insert_pos..]
-        );
-        fs::write(&listing_path, new_content)
-            .map_err(|e| format!("Failed to update blog listing: {}", e))?;
-    } else {
-        return Err("Could not find posts array in blog listing".to_string());
-    }
-
-    Ok(format!("Created post: {}", slug))
-}
-
-#[tauri::command]
-pub fn get_existing_posts(repo_path: String) -> Result<Vec<ExistingPost>, String> {
-    let blog_dir = Path::new(&repo_path)
-        .join("src")
-        .join("routes")
-        .join("blog");
-
-    if !blog_dir.exists() {
-        return Err("Blog directory not found".to_string());
-    }
-
-    let mut posts = Vec::new();
-    
-    for entry in fs::read_dir(&blog_dir).map_err(|e| e.to_string())? {
-        let entry = entry.map_err(|e| e.to_string())?;
-        let path = entry.path();
-        
-        if path.is_dir() {
-            let slug = path.file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("")
-                .to_string();
-            
-            // Skip if not a post directory
-            if slug.is_empty() || slug.starts_with('.') {
-                continue;
-            }
-
-            posts.push(ExistingPost {
-                slug: slug.clone(),
-                title: slug.replace('-', " "),
-                date: String::new(),
-                excerpt: String::new(),
-                tags: vec![],
-            });
-        }
-    }
-
-    Ok(posts)
-}
-
-#[tauri::command]
-pub fn slugify(text: String) -> String {
-    text.to_lowercase()
-        .chars()
-        .map(|c| if c.is_alphanumeric() { c } else { '-' })
-        .collect::<String>()
-        .split('-')
-        .filter(|s| !s.is_empty())
-        .collect::<Vec<_>>()
-        .join("-")
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct PostContent {
-    pub slug: String,
-    pub title: String,
-    pub date: String,
-    pub excerpt: String,
-    pub tags: Vec<String>,
-    pub content: String,
-}
-
-/// Read a post's content from the file system
-#[tauri::command]
-pub fn read_post(repo_path: String, slug: String) -> Result<PostContent, String> {
-    let post_file = Path::new(&repo_path)
-        .join("src")
-        .join("routes")
-        .join("blog")
-        .join(&slug)
-        .join("+page.md");
-
-    if !post_file.exists() {
-        return Err(format!("Post not found: {}", slug));
-    }
-
-    let file_content = fs::read_to_string(&post_file)
-        .map_err(|e| format!("Failed to read post: {}", e))?;
-
-    // Parse metadata from the file
-    let mut title = slug.replace('-', " ");
-    let mut date = String::new();
-    let mut excerpt = String::new();
-    let mut tags = Vec::new();
-    let mut content = String::new();
-
-    // Extract metadata from script block
-    if let Some(start) = file_content.find("export const metadata = {") {
-        if let Some(end) = file_content[start..].find("};") {
-            let metadata_block = &file_content[start..start + end + 2];
-            
-            // Extract title
-            if let Some(title_start) = metadata_block.find("title: \"") {
-                let title_rest = &metadata_block[title_start + 8..];
-                if let Some(title_end) = title_rest.find("\"") {
-                    title = title_rest[..title_end].to_string();
-                }
-            }
-            
-            // Extract date
-            if let Some(date_start) = metadata_block.find("date: \"") {
-                let date_rest = &metadata_block[date_start + 7..];
-                if let Some(date_end) = date_rest.find("\"") {
-                    date = date_rest[..date_end].to_string();
-                }
-            }
-            
-            // Extract excerpt
-            if let Some(excerpt_start) = metadata_block.find("excerpt: \"") {
-                let excerpt_rest = &metadata_block[excerpt_start + 10..];
-                if let Some(excerpt_end) = excerpt_rest.find("\"") {
-                    excerpt = excerpt_rest[..excerpt_end].to_string();
-                }
-            }
-            
-            // Extract tags
-            if let Some(tags_start) = metadata_block.find("tags: [") {
-                let tags_rest = &metadata_block[tags_start + 7..];
-                if let Some(tags_end) = tags_rest.find("]") {
-                    let tags_str = &tags_rest[..tags_end];
-                    tags = tags_str
-                        .split(',')
-                        .map(|t| t.trim().trim_matches('"').to_string())
-                        .filter(|t| !t.is_empty())
-                        .collect();
-                }
-            }
-        }
-    }
-
-    // Extract content from post-content div
-    if let Some(content_start) = file_content.find("<div class=\"post-content\">") {
-        let after_div = &file_content[content_start + 26..];
-        if let Some(content_end) = after_div.find("</div>") {
-            content = after_div[..content_end].trim().to_string();
-        }
-    }
-
-    Ok(PostContent {
-        slug,
-        title,
-        date,
-        excerpt,
-        tags,
-        content,
-    })
-}
-
-/// Update an existing post
-#[tauri::command]
-pub fn update_post(
-    repo_path: String,
-    slug: String,
-    title: String,
-    excerpt: String,
-    content: String,
-    tags: Vec<String>,
-) -> Result<String, String> {
-    let post_file = Path::new(&repo_path)
-        .join("src")
-        .join("routes")
-        .join("blog")
-        .join(&slug)
-        .join("+page.md");
-
-    if !post_file.exists() {
-        return Err(format!("Post not found: {}", slug));
-    }
-
-    // Read existing content to preserve date
-    let existing = read_post(repo_path.clone(), slug.clone())?;
-    
-    let metadata = PostMetadata {
-        title,
-        slug: slug.clone(),
-        excerpt,
-        content,
-        tags,
-        date: existing.date,
-        keywords: String::new(),
-    };
-
-    let post_content = generate_post_template(&metadata);
-    
-    fs::write(&post_file, post_content)
-        .map_err(|e| format!("Failed to write post: {}", e))?;
-
-    Ok(format!("Updated post: {}", slug))
-}
-
+use crate::assets::{asset_dir, hash_bytes, manifest_path};
+use crate::config::{load_config, AssetLocation, BlogConfig};
+use crate::diff::three_way_diff;
+use crate::error::AppError;
+use crate::git::{check_post_branch, commit_post_on_branch, post_branch};
+use crate::history::{content_by_version, record_current, record_revision};
+use crate::markdown::{headings, reading_time, render_html, word_count, Heading};
+use crate::seo::{canonical_url, citations, escape_attr, js_literal};
+use crate::series::{stage_posts, with_current_series_nav, with_series_nav};
+use crate::social_card::stage_social_card;
+use crate::summary::{derive_excerpt, generate_keywords};
+use crate::svelte::{escape_svelte, unescape_svelte};
+use crate::transaction::Transaction;
+use crate::watcher::expect_removal;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PostMetadata {
+    pub title: String,
+    pub slug: String,
+    pub excerpt: String,
+    pub content: String,
+    pub tags: Vec<String>,
+    pub date: String,
+    pub updated: Option<String>,
+    /// Social card URL for `og:image`/`twitter:image`
+    pub image: Option<String>,
+    /// Name of the series the post is a part of
+    pub series: Option<String>,
+    /// Position within the series, starting at 1
+    pub series_order: Option<u32>,
+    /// Render a table of contents from the headings
+    pub toc: bool,
+    pub keywords: String,
+    pub word_count: usize,
+    pub reading_time: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExistingPost {
+    pub slug: String,
+    pub title: String,
+    pub date: String,
+    pub excerpt: String,
+    pub tags: Vec<String>,
+}
+
+/// Directory holding one sub-directory per post
+pub(crate) fn blog_dir(repo_path: &str) -> PathBuf {
+    Path::new(repo_path)
+        .join("src")
+        .join("routes")
+        .join("blog")
+}
+
+/// Deepest heading level listed in a table of contents
+const TOC_MAX_LEVEL: usize = 3;
+
+/// Table of contents linking to the post's headings (empty if it has none)
+fn toc_nav(headings: &[Heading]) -> String {
+    let items: String = headings
+        .iter()
+        .map(|h| format!(
+            "                <li class=\"toc-level-{}\"><a href=\"#{}\">{}</a></li>\n",
+            h.level,
+            h.id,
+            escape_attr(&h.text)
+        ))
+        .collect();
+    format!(
+        "\n\n        <nav class=\"toc\" aria-label=\"Contents\">\n            <h2>Contents</h2>\n            <ul>\n{}            </ul>\n        </nav>",
+        items
+    )
+}
+
+/// Generate the post template (Svelte + Markdown). `citations` are the
+/// JSON-LD entries for the references the post cites.
+fn generate_post_template(metadata: &PostMetadata, config: &BlogConfig, citations: &[Value]) -> String {
+    let tags_str = metadata.tags.iter()
+        .map(|t| format!("\"{}\"", t))
+        .collect::<Vec<_>>()
+        .join(", ");
+    
+    // Use provided content or default placeholder
+    let post_content = if metadata.content.is_empty() {
+        format!("# {}\n\n<!-- Write your content here -->", metadata.title)
+    } else {
+        escape_svelte(&metadata.content)
+    };
+
+    let updated_str = metadata.updated.as_ref()
+        .map(|u| format!("\n        updated: \"{}\",", u))
+        .unwrap_or_default();
+
+    let image_str = metadata.image.as_ref()
+        .map(|i| format!("\n        image: \"{}\",", i))
+        .unwrap_or_default();
+
+    let series_str = metadata.series.as_ref()
+        .map(|s| format!("\n        series: {},", js_literal(json!(s))))
+        .unwrap_or_default();
+    let series_order_str = metadata.series_order
+        .filter(|_| metadata.series.is_some())
+        .map(|n| format!("\n        seriesOrder: {},", n))
+        .unwrap_or_default();
+
+    let site_name = escape_attr(&config.site_name);
+    let canonical = canonical_url(config, &metadata.slug);
+    let canonical_tags = canonical.as_ref()
+        .map(|url| format!(
+            "\n    <link rel=\"canonical\" href=\"{0}\" />\n    <meta property=\"og:url\" content=\"{0}\" />",
+            escape_attr(url)
+        ))
+        .unwrap_or_default();
+
+    let url_str = canonical
+        .map(|url| format!("\n        url: {},", js_literal(json!(url))))
+        .unwrap_or_default();
+    let author_str = if config.author.trim().is_empty() {
+        String::new()
+    } else {
+        format!("\n        author: {},", js_literal(json!({ "@type": "Person", "name": config.author })))
+    };
+
+    let toc_field = if metadata.toc { "\n        toc: true," } else { "" };
+
+    // The ids match rehype-slug's; the script sets them on headings in case
+    // the site does not. It slugs each rendered heading the same way rather
+    // than going by position, since the site may render headings of its own.
+    let all_headings = if metadata.toc { headings(&metadata.content) } else { Vec::new() };
+    let toc_headings: Vec<Heading> = all_headings.iter().filter(|h| h.level <= TOC_MAX_LEVEL).cloned().collect();
+    let (toc_import, toc_script, toc) = if toc_headings.is_empty() {
+        Default::default()
+    } else {
+        let ids = Value::Array(all_headings.iter().map(|h| json!(h.id)).collect());
+        (
+            "\n    import { onMount } from 'svelte';".to_string(),
+            format!(
+                r#"
+
+    // Anchor ids for the table of contents
+    const headingIds = new Set<string>({});
+    onMount(() => {{
+        // Same slugs and `-1`, `-2` suffixes as github-slugger
+        const seen = new Map<string, number>();
+        document.querySelectorAll('.post-content :is(h1, h2, h3, h4, h5, h6)').forEach((heading) => {{
+            const base = (heading.textContent ?? '').trim().toLowerCase()
+                .replace(/[^\p{{Alphabetic}}\p{{N}} _-]/gu, '').replace(/ /g, '-');
+            let id = base;
+            while (seen.has(id)) {{
+                const count = (seen.get(base) ?? 0) + 1;
+                seen.set(base, count);
+                id = `${{base}}-${{count}}`;
+            }}
+            seen.set(id, 0);
+            if (!heading.id && headingIds.has(id)) heading.id = id;
+        }});
+    }});"#,
+                js_literal(ids)
+            ),
+            toc_nav(&toc_headings),
+        )
+    };
+
+    format!(
+        r#"<script context="module">
+    export const metadata = {{
+        title: {},
+        date: "{}",{}
+        excerpt: {},
+        keywords: {},
+        tags: [{}],{}{}{}{toc_field}
+        wordCount: {},
+        readingTime: {}
+    }};
+
+    export const citations = {};
+</script>
+
+<script lang="ts">
+    import Citation from '$lib/components/Citation.svelte';
+    import References from '$lib/components/References.svelte';
+    import {{ related }} from '$lib/related';{toc_import}
+
+    const relatedPosts = related[{}] ?? [];{toc_script}
+    
+    function formatDate(dateString: string): string {{
+        return new Date(dateString).toLocaleDateString('en-US', {{
+            year: 'numeric', month: 'long', day: 'numeric'
+        }});
+    }}
+
+    const jsonLd = JSON.stringify({{
+        "@context": "https://schema.org",
+        "@type": "BlogPosting",
+        headline: metadata.title,
+        description: metadata.excerpt,
+        datePublished: metadata.date,
+        dateModified: metadata.updated || metadata.date,
+        keywords: metadata.keywords,
+        wordCount: metadata.wordCount,
+        image: metadata.image,{}{}
+        publisher: {{ "@type": "Organization", name: {} }},
+        citation: citations
+    }}).replace(/</g, "\\u003c");
+</script>
+
+<svelte:head>
+    <title>{{metadata.title}} | {}</title>
+    <meta name="description" content={{metadata.excerpt}} />
+    <meta name="keywords" content={{metadata.keywords}} />{}
+    <meta property="og:type" content="article" />
+    <meta property="og:site_name" content="{}" />
+    <meta property="og:title" content={{metadata.title}} />
+    <meta property="og:description" content={{metadata.excerpt}} />
+    <meta property="article:published_time" content={{metadata.date}} />
+    {{#if metadata.updated}}
+        <meta property="article:modified_time" content={{metadata.updated}} />
+    {{/if}}
+    {{#each metadata.tags as tag}}
+        <meta property="article:tag" content={{tag}} />
+    {{/each}}
+    <meta name="twitter:card" content={{metadata.image ? "summary_large_image" : "summary"}} />
+    <meta name="twitter:title" content={{metadata.title}} />
+    <meta name="twitter:description" content={{metadata.excerpt}} />
+    {{#if metadata.image}}
+        <meta property="og:image" content={{metadata.image}} />
+        <meta name="twitter:image" content={{metadata.image}} />
+    {{/if}}
+    {{@html `<script type="application/ld+json">${{jsonLd}}</script>`}}
+</svelte:head>
+
+<article class="blog-post">
+    <div class="container">
+        <header class="post-header">
+            <a href="/blog" class="back-link">
+                <svg width="20" height="20" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2">
+                    <path d="M19 12H5M12 19l-7-7 7-7" />
+                </svg>
+                Back to Blog
+            </a>
+            <div class="post-meta">
+                <time datetime={{metadata.date}}>{{formatDate(metadata.date)}}</time>
+                {{#if metadata.updated}}
+                    <span class="post-updated">Updated <time datetime={{metadata.updated}}>{{formatDate(metadata.updated)}}</time></span>
+                {{/if}}
+                <span class="reading-time">{{metadata.readingTime}} min read</span>
+                <div class="post-tags">
+                    {{#each metadata.tags as tag}}
+                        <span class="tag">{{tag}}</span>
+                    {{/each}}
+                </div>
+            </div>
+            <h1 class="post-title">{{metadata.title}}</h1>
+        </header>{toc}
+        
+        <div class="post-content">
+
+{}
+
+        </div>
+
+        {{#if relatedPosts.length}}
+            <aside class="related-posts">
+                <h2>Related posts</h2>
+                <ul>
+                    {{#each relatedPosts as post}}
+                        <li><a href="/blog/{{post.slug}}">{{post.title}}</a></li>
+                    {{/each}}
+                </ul>
+            </aside>
+        {{/if}}
+        
+        <footer class="post-footer">
+            <a href="/blog" class="back-link">
+                <svg width="20" height="20" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2">
+                    <path d="M19 12H5M12 19l-7-7 7-7" />
+                </svg>
+                Back to Blog
+            </a>
+        </footer>
+    </div>
+</article>
+
+<style>
+    .blog-post {{ min-height: calc(100vh - 72px); padding: var(--space-4xl) 0; background: var(--color-bg-primary); }}
+    .blog-post .container {{ max-width: 800px; }}
+    .back-link {{ display: inline-flex; align-items: center; gap: var(--space-sm); color: var(--color-text-secondary); font-size: var(--font-size-sm); font-weight: 500; margin-bottom: var(--space-xl); transition: color var(--transition-fast); }}
+    .back-link:hover {{ color: var(--color-accent); }}
+    .post-header {{ margin-bottom: var(--space-2xl); }}
+    .post-meta {{ display: flex; align-items: center; gap: var(--space-md); margin-bottom: var(--space-lg); flex-wrap: wrap; }}
+    .post-meta time, .post-updated, .reading-time {{ font-size: var(--font-size-sm); color: var(--color-text-muted); }}
+    .post-tags {{ display: flex; gap: var(--space-xs); flex-wrap: wrap; }}
+    .tag {{ font-size: var(--font-size-xs); padding: var(--space-xs) var(--space-sm); background: rgba(59, 130, 246, 0.15); color: var(--color-accent-light); border-radius: var(--radius-sm); font-weight: 500; }}
+    .post-title {{ font-size: var(--font-size-4xl); font-weight: 700; line-height: 1.2; color: var(--color-text-primary); }}
+    .post-content {{ color: var(--color-text-secondary); line-height: 1.8; font-size: var(--font-size-lg); }}
+    .post-content :global(h1), .post-content :global(h2), .post-content :global(h3) {{ color: var(--color-text-primary); margin-top: var(--space-2xl); margin-bottom: var(--space-md); font-weight: 600; }}
+    .post-content :global(h1) {{ font-size: var(--font-size-3xl); }}
+    .post-content :global(h2) {{ font-size: var(--font-size-2xl); }}
+    .post-content :global(h3) {{ font-size: var(--font-size-xl); }}
+    .post-content :global(p) {{ margin-bottom: var(--space-lg); }}
+    .post-content :global(a) {{ color: var(--color-accent-light); text-decoration: underline; }}
+    .post-content :global(ul), .post-content :global(ol) {{ margin-bottom: var(--space-lg); padding-left: var(--space-xl); }}
+    .post-content :global(li) {{ margin-bottom: var(--space-sm); }}
+    .post-content :global(blockquote) {{ border-left: 3px solid var(--color-accent); background: rgba(59, 130, 246, 0.1); padding: var(--space-md) var(--space-lg); margin: var(--space-lg) 0; border-radius: 0 var(--radius-lg) var(--radius-lg) 0; font-style: italic; }}
+    .post-content :global(strong) {{ color: var(--color-text-primary); font-weight: 600; }}
+    .post-content :global(em) {{ font-style: italic; }}
+    .post-content :global(hr) {{ border: none; border-top: 1px solid var(--color-border); margin: var(--space-2xl) 0; }}
+    .blog-post :global(.toc) {{ margin-bottom: var(--space-2xl); font-size: var(--font-size-sm); }}
+    .blog-post :global(.toc h2) {{ font-size: var(--font-size-base); font-weight: 600; color: var(--color-text-primary); margin-bottom: var(--space-sm); }}
+    .blog-post :global(.toc ul) {{ list-style: none; padding: 0; }}
+    .blog-post :global(.toc a) {{ color: var(--color-text-secondary); }}
+    .blog-post :global(.toc a:hover) {{ color: var(--color-accent); }}
+    .blog-post :global(.toc-level-2) {{ padding-left: var(--space-md); }}
+    .blog-post :global(.toc-level-3) {{ padding-left: var(--space-xl); }}
+    .blog-post :global(.series-nav) {{ margin-bottom: var(--space-2xl); padding: var(--space-lg); border: 1px solid var(--color-border); border-radius: var(--radius-lg); font-size: var(--font-size-sm); color: var(--color-text-secondary); }}
+    .blog-post :global(.series-nav ol) {{ margin: var(--space-sm) 0 0; padding-left: var(--space-xl); }}
+    .blog-post :global(.series-nav a), .blog-post :global(.series-links a) {{ color: var(--color-accent-light); }}
+    .blog-post :global(.series-links) {{ display: flex; justify-content: space-between; gap: var(--space-md); margin-top: var(--space-2xl); font-size: var(--font-size-sm); }}
+    .blog-post :global(.series-next) {{ margin-left: auto; text-align: right; }}
+    .related-posts {{ margin-top: var(--space-2xl); padding-top: var(--space-xl); border-top: 1px solid var(--color-border); }}
+    .related-posts h2 {{ font-size: var(--font-size-xl); font-weight: 600; color: var(--color-text-primary); margin-bottom: var(--space-md); }}
+    .related-posts ul {{ list-style: none; padding: 0; display: flex; flex-direction: column; gap: var(--space-sm); }}
+    .related-posts a {{ color: var(--color-accent-light); }}
+    .post-footer {{ margin-top: var(--space-4xl); padding-top: var(--space-xl); border-top: 1px solid var(--color-border); }}
+</style>
+"#,
+        js_literal(json!(metadata.title)),
+        metadata.date,
+        updated_str,
+        js_literal(json!(metadata.excerpt)),
+        js_literal(json!(metadata.keywords)),
+        tags_str,
+        image_str,
+        series_str,
+        series_order_str,
+        metadata.word_count,
+        metadata.reading_time,
+        js_literal(Value::Array(citations.to_vec())),
+        js_literal(json!(metadata.slug)),
+        url_str,
+        author_str,
+        js_literal(json!(config.site_name)),
+        site_name,
+        canonical_tags,
+        site_name,
+        post_content,
+        toc_field = toc_field,
+        toc_import = toc_import,
+        toc_script = toc_script,
+        toc = toc,
+    )
+}
+
+/// Generate the listing entry to add to +page.svelte
+fn generate_listing_entry(metadata: &PostMetadata) -> String {
+    let tags_str = metadata.tags.iter()
+        .map(|t| format!("\"{}\"", t))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let updated_str = metadata.updated.as_ref()
+        .map(|u| format!("\n            updated: \"{}\",", u))
+        .unwrap_or_default();
+
+    format!(
+        r#"        {{
+            slug: "{}",
+            title: {},
+            date: "{}",{}
+            excerpt: {},
+            tags: [{}],
+            content: {},
+            wordCount: {},
+            readingTime: {},
+        }},"#,
+        metadata.slug,
+        js_literal(json!(metadata.title)),
+        metadata.date,
+        updated_str,
+        js_literal(json!(metadata.excerpt)),
+        tags_str,
+        js_literal(json!(metadata.keywords)),
+        metadata.word_count,
+        metadata.reading_time
+    )
+}
+
+/// Locate the listing entry for `slug`, returning the byte range of its lines
+/// (from the indentation before `{` through the closing `},`)
+fn find_listing_entry(listing_content: &str, slug: &str) -> Option<(usize, usize)> {
+    let slug_pos = listing_content.find(&format!("slug: \"{}\"", slug))?;
+    let open = listing_content[..slug_pos].rfind('{')?;
+    let start = listing_content[..open].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let close = slug_pos + listing_content[slug_pos..].find("},")?;
+    Some((start, close + 2))
+}
+
+/// Read a string field such as `content: "..."` out of generated JS. Values
+/// are written as JSON string literals; older unescaped ones read as they are.
+fn js_field(block: &str, field: &str) -> Option<String> {
+    let marker = format!("{}: \"", field);
+    let literal = &block[block.find(&marker)? + marker.len() - 1..];
+    serde_json::Deserializer::from_str(literal)
+        .into_iter::<String>()
+        .next()
+        .and_then(Result::ok)
+        .or_else(|| {
+            let end = literal[1..].find('"')?;
+            Some(literal[1..end + 1].to_string())
+        })
+}
+
+/// Keywords from a post's listing entry, where older posts keep them as `content:`
+fn listing_keywords(repo_path: &str, slug: &str) -> Option<String> {
+    let listing = fs::read_to_string(blog_dir(repo_path).join("+page.svelte")).ok()?;
+    let (start, end) = find_listing_entry(&listing, slug)?;
+    js_field(&listing[start..end], "content").filter(|k| !k.trim().is_empty())
+}
+
+/// The metadata saving a post writes. A blank excerpt is derived from the
+/// content; blank keywords fall back to the existing post's (listing entry
+/// first) and are otherwise generated. An existing post keeps its date,
+/// image, series and table of contents and is marked updated today.
+#[allow(clippy::too_many_arguments)]
+fn post_metadata(
+    repo_path: &str,
+    config: &BlogConfig,
+    existing: Option<&PostContent>,
+    title: String,
+    slug: String,
+    excerpt: String,
+    content: String,
+    tags: Vec<String>,
+    keywords: String,
+) -> Result<PostMetadata, AppError> {
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let excerpt = if excerpt.trim().is_empty() {
+        derive_excerpt(&content, config.excerpt_length)
+    } else {
+        excerpt
+    };
+    let keywords = Some(keywords)
+        .filter(|k| !k.trim().is_empty())
+        .or_else(|| existing.and_then(|e| listing_keywords(repo_path, &e.slug)))
+        .or_else(|| existing.map(|e| e.keywords.clone()).filter(|k| !k.trim().is_empty()));
+    let keywords = match keywords {
+        Some(keywords) => keywords,
+        None => generate_keywords(repo_path, &slug, &title, &content, config.keyword_count)?,
+    };
+
+    let word_count = word_count(&content);
+    Ok(PostMetadata {
+        date: existing.map_or_else(|| today.clone(), |e| e.date.clone()),
+        updated: existing.map(|_| today),
+        image: existing.map(|e| e.image.clone()).filter(|i| !i.is_empty()),
+        series: existing.map(|e| e.series.clone()).filter(|s| !s.is_empty()),
+        series_order: existing.and_then(|e| e.series_order),
+        toc: existing.is_some_and(|e| e.toc),
+        title,
+        slug,
+        excerpt,
+        content,
+        tags,
+        keywords,
+        word_count,
+        reading_time: reading_time(word_count),
+    })
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn create_blog_post(
+    repo_path: String,
+    title: String,
+    slug: String,
+    excerpt: String,
+    content: String,
+    tags: Vec<String>,
+    keywords: String,
+    branch: Option<bool>,
+) -> Result<String, AppError> {
+    let config = load_config(&repo_path)?;
+    let metadata = post_metadata(&repo_path, &config, None, title, slug.clone(), excerpt, content, tags, keywords)?;
+
+    // Create post directory
+    let post_dir = Path::new(&repo_path)
+        .join("src")
+        .join("routes")
+        .join("blog")
+        .join(&slug);
+
+    if post_dir.exists() {
+        return Err(AppError::PostExists { slug, path: post_dir });
+    }
+
+    // Prepare both files before touching anything
+    let post_content = generate_post_template(&metadata, &config, &citations(&repo_path, &metadata.content));
+
+    let listing_path = Path::new(&repo_path)
+        .join("src")
+        .join("routes")
+        .join("blog")
+        .join("+page.svelte");
+
+    let listing_content = fs::read_to_string(&listing_path)
+        .map_err(|e| AppError::io("read blog listing", &listing_path, e))?;
+
+    let new_entry = generate_listing_entry(&metadata);
+    let insert_marker = "const posts = [";
+    let insert_pos = listing_content
+        .find(insert_marker)
+        .map(|pos| pos + insert_marker.len())
+        .ok_or_else(|| AppError::ListingMarkerMissing { path: listing_path.clone() })?;
+    let new_listing = format!(
+        "{}\n{}{}",
+        &listing_content[..insert_pos],
+        new_entry,
+        &listing_content[insert_pos..]
+    );
+
+    // Draft on its own branch, committing the new files there once written
+    let branch = branch.unwrap_or(false);
+    if branch {
+        check_post_branch(&repo_path, &slug)?;
+    }
+
+    let mut transaction = Transaction::default();
+    transaction.write(&listing_path, new_listing);
+    let written = stage_posts(&mut transaction, &repo_path, vec![(slug.clone(), Some(post_content.clone()))])?;
+    transaction.commit()?;
+
+    if let Err(e) = record_revision(&repo_path, &slug, &post_content) {
+        log::warn!("Failed to record revision for {}: {}", slug, e);
+    }
+
+    // The post exists either way, so a failed commit is not an error
+    if branch {
+        return Ok(match commit_post_on_branch(&repo_path, &slug, &written) {
+            Ok(()) => format!("Created post: {} on branch {}", slug, post_branch(&slug)),
+            Err(e) => {
+                log::warn!("Failed to commit {} on a branch: {}", slug, e);
+                format!("Created post: {}, but could not commit it on a branch: {}", slug, e)
+            }
+        });
+    }
+
+    Ok(format!("Created post: {}", slug))
+}
+
+#[tauri::command]
+pub fn get_existing_posts(repo_path: String) -> Result<Vec<ExistingPost>, AppError> {
+    let blog_dir = Path::new(&repo_path)
+        .join("src")
+        .join("routes")
+        .join("blog");
+
+    if !blog_dir.exists() {
+        return Err(AppError::BlogDirNotFound { path: blog_dir });
+    }
+
+    let mut posts = Vec::new();
+    
+    for entry in fs::read_dir(&blog_dir).map_err(|e| AppError::io("list posts", &blog_dir, e))? {
+        let entry = entry.map_err(|e| AppError::io("list posts", &blog_dir, e))?;
+        let path = entry.path();
+        
+        if path.is_dir() {
+            let slug = path.file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("")
+                .to_string();
+            
+            // Skip if not a post directory
+            if slug.is_empty() || slug.starts_with('.') {
+                continue;
+            }
+
+            posts.push(ExistingPost {
+                slug: slug.clone(),
+                title: slug.replace('-', " "),
+                date: String::new(),
+                excerpt: String::new(),
+                tags: vec![],
+            });
+        }
+    }
+
+    Ok(posts)
+}
+
+/// Every post file in the repo as `(slug, content)`, sorted by slug (none if
+/// the blog directory is missing)
+pub(crate) fn load_post_files(repo_path: &str) -> Result<Vec<(String, String)>, AppError> {
+    let blog = blog_dir(repo_path);
+    if !blog.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut files = Vec::new();
+    for entry in fs::read_dir(&blog).map_err(|e| AppError::io("list posts", &blog, e))? {
+        let entry = entry.map_err(|e| AppError::io("list posts", &blog, e))?;
+        let slug = entry.file_name().to_string_lossy().to_string();
+        let post_file = entry.path().join("+page.md");
+
+        if slug.starts_with('.') || !post_file.exists() {
+            continue;
+        }
+
+        let file_content = fs::read_to_string(&post_file)
+            .map_err(|e| AppError::io("read post", &post_file, e))?;
+        files.push((slug, file_content));
+    }
+
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(files)
+}
+
+/// Read and parse every post in the repo (none if the blog directory is missing)
+pub(crate) fn load_posts(repo_path: &str) -> Result<Vec<PostContent>, AppError> {
+    Ok(load_post_files(repo_path)?
+        .into_iter()
+        .map(|(slug, file_content)| parse_post(slug, &file_content))
+        .collect())
+}
+
+#[tauri::command]
+pub fn slugify(text: String) -> String {
+    text.to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect::<String>()
+        .split('-')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PostContent {
+    pub slug: String,
+    pub title: String,
+    pub date: String,
+    pub updated: String,
+    pub excerpt: String,
+    pub tags: Vec<String>,
+    pub keywords: String,
+    pub image: String,
+    /// Series the post belongs to; empty if none
+    pub series: String,
+    pub series_order: Option<u32>,
+    pub toc: bool,
+    pub content: String,
+    pub word_count: usize,
+    pub reading_time: usize,
+    /// Hash of the file as read (see `post_version`); `update_post` uses it to
+    /// detect outside edits
+    pub version: String,
+}
+
+/// Read a post's content from the file system
+#[tauri::command]
+pub fn read_post(repo_path: String, slug: String) -> Result<PostContent, AppError> {
+    let post_file = Path::new(&repo_path)
+        .join("src")
+        .join("routes")
+        .join("blog")
+        .join(&slug)
+        .join("+page.md");
+
+    if !post_file.exists() {
+        return Err(AppError::PostNotFound { slug, path: post_file });
+    }
+
+    let file_content = fs::read_to_string(&post_file)
+        .map_err(|e| AppError::io("read post", &post_file, e))?;
+
+    Ok(parse_post(slug, &file_content))
+}
+
+/// Read a post to edit it. Unlike `read_post`, this keeps the version handed
+/// out in the history, so that a conflicting save can later be diffed
+/// against it.
+#[tauri::command]
+pub fn open_post_for_edit(repo_path: String, slug: String) -> Result<PostContent, AppError> {
+    let post_file = blog_dir(&repo_path).join(&slug).join("+page.md");
+    if !post_file.exists() {
+        return Err(AppError::PostNotFound { slug, path: post_file });
+    }
+
+    let file_content = fs::read_to_string(&post_file)
+        .map_err(|e| AppError::io("read post", &post_file, e))?;
+
+    if let Err(e) = record_revision(&repo_path, &slug, &file_content) {
+        log::warn!("Failed to record revision for {}: {}", slug, e);
+    }
+
+    Ok(parse_post(slug, &file_content))
+}
+
+/// Parse the metadata block and body out of a generated `+page.md`
+pub(crate) fn parse_post(slug: String, file_content: &str) -> PostContent {
+    // Parse metadata from the file
+    let mut title = slug.replace('-', " ");
+    let mut date = String::new();
+    let mut updated = String::new();
+    let mut image = String::new();
+    let mut series = String::new();
+    let mut series_order = None;
+    let mut toc = false;
+    let mut keywords = String::new();
+    let mut excerpt = String::new();
+    let mut tags = Vec::new();
+    let mut content = String::new();
+
+    // Extract metadata from script block
+    if let Some(start) = file_content.find("export const metadata = {") {
+        if let Some(end) = file_content[start..].find("};") {
+            let metadata_block = &file_content[start..start + end + 2];
+            
+            if let Some(value) = js_field(metadata_block, "title") {
+                title = value;
+            }
+            date = js_field(metadata_block, "date").unwrap_or_default();
+            updated = js_field(metadata_block, "updated").unwrap_or_default();
+            image = js_field(metadata_block, "image").unwrap_or_default();
+
+            // Series and position within it
+            series = js_field(metadata_block, "series").unwrap_or_default();
+            if let Some(order_start) = metadata_block.find("seriesOrder: ") {
+                series_order = metadata_block[order_start + 13..]
+                    .split(|c: char| !c.is_ascii_digit())
+                    .next()
+                    .and_then(|n| n.parse().ok());
+            }
+
+            toc = metadata_block.contains("toc: true");
+            excerpt = js_field(metadata_block, "excerpt").unwrap_or_default();
+            keywords = js_field(metadata_block, "keywords").unwrap_or_default();
+
+            // Extract tags
+            if let Some(tags_start) = metadata_block.find("tags: [") {
+                let tags_rest = &metadata_block[tags_start + 7..];
+                if let Some(tags_end) = tags_rest.find("]") {
+                    let tags_str = &tags_rest[..tags_end];
+                    tags = tags_str
+                        .split(',')
+                        .map(|t| t.trim().trim_matches('"').to_string())
+                        .filter(|t| !t.is_empty())
+                        .collect();
+                }
+            }
+        }
+    }
+
+    // Extract content from post-content div
+    if let Some(content_start) = file_content.find("<div class=\"post-content\">") {
+        let after_div = &file_content[content_start + 26..];
+        if let Some(content_end) = after_div.find("</div>") {
+            content = unescape_svelte(after_div[..content_end].trim());
+        }
+    }
+
+    let word_count = word_count(&content);
+
+    PostContent {
+        slug,
+        title,
+        date,
+        updated,
+        excerpt,
+        tags,
+        keywords,
+        image,
+        series,
+        series_order,
+        toc,
+        content,
+        word_count,
+        reading_time: reading_time(word_count),
+        version: post_version(file_content),
+    }
+}
+
+/// Hash of a post file without its series navigation, which saving another
+/// part of the series rewrites and so is never an outside edit
+pub(crate) fn post_version(file_content: &str) -> String {
+    hash_bytes(with_series_nav(file_content, None).as_bytes())
+}
+
+/// Update an existing post. `version` is the one `open_post_for_edit`
+/// returned; if the file has changed since, nothing is written and a conflict
+/// is returned.
+#[tauri::command]
+pub fn update_post(
+    repo_path: String,
+    slug: String,
+    title: String,
+    excerpt: String,
+    content: String,
+    tags: Vec<String>,
+    version: String,
+) -> Result<String, AppError> {
+    let post_file = Path::new(&repo_path)
+        .join("src")
+        .join("routes")
+        .join("blog")
+        .join(&slug)
+        .join("+page.md");
+
+    if !post_file.exists() {
+        return Err(AppError::PostNotFound { slug, path: post_file });
+    }
+
+    // Read existing content to preserve date
+    let existing = read_post(repo_path.clone(), slug.clone())?;
+
+    if existing.version != version {
+        let base = content_by_version(&repo_path, &slug, &version).map(|c| parse_post(slug.clone(), &c));
+        let editor = PostContent {
+            title,
+            excerpt,
+            content,
+            tags,
+            ..existing.clone()
+        };
+        return Err(AppError::Conflict {
+            slug,
+            path: post_file,
+            diff: Box::new(three_way_diff(base.as_ref(), &existing, &editor)),
+            version: existing.version,
+        });
+    }
+
+    let listing_path = blog_dir(&repo_path).join("+page.svelte");
+    let listing_content = fs::read_to_string(&listing_path)
+        .map_err(|e| AppError::io("read blog listing", &listing_path, e))?;
+    let listing_entry = find_listing_entry(&listing_content, &slug);
+
+    let config = load_config(&repo_path)?;
+    let metadata = post_metadata(
+        &repo_path,
+        &config,
+        Some(&existing),
+        title,
+        slug.clone(),
+        excerpt,
+        content,
+        tags,
+        String::new(),
+    )?;
+
+    let post_content = generate_post_template(&metadata, &config, &citations(&repo_path, &metadata.content));
+
+    let mut transaction = Transaction::default();
+
+    match listing_entry {
+        Some((start, end)) => {
+            let new_content = format!(
+                "{}{}{}",
+                &listing_content[..start],
+                generate_listing_entry(&metadata),
+                &listing_content[end..]
+            );
+            transaction.write(&listing_path, new_content);
+        }
+        None => log::warn!("No listing entry found for post: {}", slug),
+    }
+
+    // Keep an existing social card in step with the new title and tags
+    if metadata.image.is_some() {
+        let post = parse_post(slug.clone(), &post_content);
+        if let Err(e) = stage_social_card(&mut transaction, &repo_path, &post, &config) {
+            log::warn!("Failed to refresh social card for {}: {}", slug, e);
+        }
+    }
+
+    // A new title shows up in the navigation of the series' other parts
+    stage_posts(&mut transaction, &repo_path, vec![(slug.clone(), Some(post_content))])?;
+
+    // Keep whatever is on disk (including edits made outside the app)
+    // before overwriting it
+    record_current(&repo_path, &slug);
+    transaction.commit()?;
+
+    Ok(format!("Updated post: {}", slug))
+}
+
+#[derive(Debug, Serialize)]
+pub struct PostPreview {
+    /// The `+page.md` that saving would write
+    pub template: String,
+    /// The Markdown body rendered to HTML
+    pub html: String,
+}
+
+/// Render a post the way saving it would: the `+page.md` that
+/// `create_blog_post` (or `update_post`, if `slug` exists) writes, and the
+/// body as HTML. With `new_post`, an existing `slug` is an error, since
+/// creating the post would fail.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub fn render_preview(
+    repo_path: String,
+    slug: String,
+    title: String,
+    excerpt: String,
+    content: String,
+    tags: Vec<String>,
+    keywords: String,
+    new_post: Option<bool>,
+) -> Result<PostPreview, AppError> {
+    let config = load_config(&repo_path)?;
+    let post_file = blog_dir(&repo_path).join(&slug).join("+page.md");
+    if new_post.unwrap_or(false) && !slug.is_empty() && post_file.exists() {
+        return Err(AppError::PostExists { slug, path: post_file });
+    }
+    let existing = Some(post_file)
+        .filter(|_| !slug.is_empty())
+        .and_then(|post_file| fs::read_to_string(post_file).ok())
+        .map(|file_content| parse_post(slug.clone(), &file_content));
+
+    let html = render_html(&content);
+    let metadata = post_metadata(&repo_path, &config, existing.as_ref(), title, slug.clone(), excerpt, content, tags, keywords)?;
+
+    let template = generate_post_template(&metadata, &config, &citations(&repo_path, &metadata.content));
+    let template = with_current_series_nav(&repo_path, &parse_post(slug, &template), &template)?;
+
+    Ok(PostPreview { template, html })
+}
+
+/// Turn the post's table of contents on or off, regenerating the page
+#[tauri::command]
+pub fn set_post_toc(repo_path: String, slug: String, enabled: bool) -> Result<String, AppError> {
+    let existing = read_post(repo_path.clone(), slug.clone())?;
+    let config = load_config(&repo_path)?;
+
+    let metadata = PostMetadata {
+        title: existing.title,
+        slug: slug.clone(),
+        excerpt: existing.excerpt,
+        content: existing.content,
+        tags: existing.tags,
+        date: existing.date,
+        updated: Some(existing.updated).filter(|u| !u.is_empty()),
+        image: Some(existing.image).filter(|i| !i.is_empty()),
+        series: Some(existing.series).filter(|s| !s.is_empty()),
+        series_order: existing.series_order,
+        toc: enabled,
+        keywords: existing.keywords,
+        word_count: existing.word_count,
+        reading_time: existing.reading_time,
+    };
+    let post_content = generate_post_template(&metadata, &config, &citations(&repo_path, &metadata.content));
+
+    let mut transaction = Transaction::default();
+    stage_posts(&mut transaction, &repo_path, vec![(slug.clone(), Some(post_content))])?;
+    record_current(&repo_path, &slug);
+    transaction.commit()?;
+
+    let state = if enabled { "on" } else { "off" };
+    Ok(format!("Turned the table of contents {} for {}", state, slug))
+}
+
+/// Delete a post: its directory, its static assets (including the social
+/// card), its listing entry and asset manifest. The series it was part of and
+/// the related posts are updated. The post's last version stays in the
+/// history.
+#[tauri::command]
+pub fn delete_post(repo_path: String, slug: String) -> Result<String, AppError> {
+    let post_dir = blog_dir(&repo_path).join(&slug);
+    let post_file = post_dir.join("+page.md");
+    if slug.is_empty() || slug.starts_with('.') || slug.contains(['/', '\\']) || !post_file.exists() {
+        return Err(AppError::PostNotFound { slug, path: post_file });
+    }
+
+    let listing_path = blog_dir(&repo_path).join("+page.svelte");
+    let listing_content = fs::read_to_string(&listing_path)
+        .map_err(|e| AppError::io("read blog listing", &listing_path, e))?;
+
+    let mut transaction = Transaction::default();
+    match find_listing_entry(&listing_content, &slug) {
+        Some((start, end)) => {
+            // Take the line break after `},` with the entry
+            let end = if listing_content[end..].starts_with('\n') { end + 1 } else { end };
+            let new_content = format!("{}{}", &listing_content[..start], &listing_content[end..]);
+            transaction.write(&listing_path, new_content);
+        }
+        None => log::warn!("No listing entry found for post: {}", slug),
+    }
+    stage_posts(&mut transaction, &repo_path, vec![(slug.clone(), None)])?;
+
+    record_current(&repo_path, &slug);
+
+    // Move the directories aside first, so they can be put back if the
+    // listing cannot be updated
+    let static_dir = asset_dir(&repo_path, &slug, AssetLocation::Static);
+    let mut moved: Vec<(PathBuf, PathBuf)> = Vec::new();
+    let restore = |moved: &[(PathBuf, PathBuf)]| {
+        for (dir, trash) in moved.iter().rev() {
+            if let Err(e) = fs::rename(trash, dir) {
+                log::error!("Failed to restore {}: {}", dir.display(), e);
+            }
+        }
+    };
+    expect_removal(&post_file);
+    for dir in [post_dir, static_dir].into_iter().filter(|dir| dir.exists()) {
+        let trash = dir.with_file_name(format!(".{}.deleted", slug));
+        if let Err(e) = fs::rename(&dir, &trash) {
+            restore(&moved);
+            return Err(AppError::io("delete post", &dir, e));
+        }
+        moved.push((dir, trash));
+    }
+    if let Err(e) = transaction.commit() {
+        restore(&moved);
+        return Err(e);
+    }
+
+    for (_, trash) in &moved {
+        if let Err(e) = fs::remove_dir_all(trash) {
+            log::warn!("Failed to remove {}: {}", trash.display(), e);
+        }
+    }
+    let manifest = manifest_path(&repo_path, &slug);
+    if manifest.exists() {
+        if let Err(e) = fs::remove_file(&manifest) {
+            log::warn!("Failed to remove {}: {}", manifest.display(), e);
+        }
+    }
+
+    Ok(format!("Deleted post: {}", slug))
+}
+
+/// Set a field of the metadata block of `post_file`, whose content is
+/// `file_content`, to the JS literal `value`, or remove it when `value` is
+/// `None`. New fields go before `wordCount`, where the template puts optional
+/// fields.
+pub(crate) fn set_metadata_field(
+    post_file: &Path,
+    file_content: &str,
+    field: &str,
+    value: Option<&str>,
+) -> Result<String, AppError> {
+    let invalid = |reason: &str| AppError::InvalidPost {
+        path: post_file.to_path_buf(),
+        reason: reason.to_string(),
+    };
+    let start = file_content
+        .find("export const metadata = {")
+        .ok_or_else(|| invalid("No metadata block in post"))?;
+    let end = start + file_content[start..]
+        .find("};")
+        .ok_or_else(|| invalid("No metadata block in post"))?;
+    let block = &file_content[start..end];
+
+    // Byte range of the line holding `name:`, including its line break
+    let line = |name: &str| {
+        let prefix = format!("{}: ", name);
+        let mut offset = 0;
+        for line in block.split_inclusive('\n') {
+            if line.trim_start().starts_with(&prefix) {
+                return Some((offset, offset + line.len()));
+            }
+            offset += line.len();
+        }
+        None
+    };
+    let new_line = value.map(|v| format!("        {}: {},\n", field, v)).unwrap_or_default();
+
+    let new_block = match line(field) {
+        Some((line_start, line_end)) => {
+            format!("{}{}{}", &block[..line_start], new_line, &block[line_end..])
+        }
+        None if value.is_none() => return Ok(file_content.to_string()),
+        None => {
+            // Older posts may lack `wordCount`; the line after `tags` will do
+            let at = line("wordCount")
+                .map(|(line_start, _)| line_start)
+                .or_else(|| line("tags").map(|(_, line_end)| line_end))
+                .ok_or_else(|| invalid("No tags field in post metadata"))?;
+            format!("{}{}{}", &block[..at], new_line, &block[at..])
+        }
+    };
+
+    Ok(format!("{}{}{}", &file_content[..start], new_block, &file_content[end..]))
+}
+
+/// Point the post's `image` metadata field at `url`, adding the field if needed
+pub(crate) fn set_post_image(transaction: &mut Transaction, repo_path: &str, slug: &str, url: &str) -> Result<(), AppError> {
+    let post_file = blog_dir(repo_path).join(slug).join("+page.md");
+    let file_content = fs::read_to_string(&post_file)
+        .map_err(|e| AppError::io("read post", &post_file, e))?;
+
+    let new_content = set_metadata_field(&post_file, &file_content, "image", Some(&js_literal(json!(url))))?;
+    transaction.write(post_file, new_content);
+    Ok(())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::history::list_revisions;
+
+    /// An empty website repo with a blog listing, unique to `name`
+    pub(crate) fn fake_repo(name: &str) -> String {
+        let dir = std::env::temp_dir().join(format!("blog-manager-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(blog_dir(&dir.to_string_lossy())).unwrap();
+        fs::write(
+            dir.join("src/routes/blog/+page.svelte"),
+            "<script>\n    const posts = [\n    ];\n</script>\n",
+        )
+        .unwrap();
+        dir.to_string_lossy().to_string()
+    }
+
+    pub(crate) fn create(repo: &str, slug: &str, title: &str, content: &str) {
+        create_blog_post(
+            repo.to_string(),
+            title.to_string(),
+            slug.to_string(),
+            "Excerpt".to_string(),
+            content.to_string(),
+            vec!["tag".to_string()],
+            "keywords".to_string(),
+            None,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn deleting_a_post_removes_everything_that_belongs_to_it() {
+        let repo = fake_repo("delete");
+        for (slug, title) in [("a", "Part A"), ("b", "Part B"), ("c", "Part C")] {
+            create(&repo, slug, title, "Ownership and borrowing in Rust");
+        }
+        crate::series::create_series(repo.clone(), "Intro".into(), vec!["a".into(), "b".into(), "c".into()]).unwrap();
+        let static_dir = asset_dir(&repo, "b", AssetLocation::Static);
+        fs::create_dir_all(&static_dir).unwrap();
+        fs::write(static_dir.join("og.png"), "png").unwrap();
+        fs::create_dir_all(manifest_path(&repo, "b").parent().unwrap()).unwrap();
+        fs::write(manifest_path(&repo, "b"), "{}").unwrap();
+
+        delete_post(repo.clone(), "b".into()).unwrap();
+
+        assert!(!blog_dir(&repo).join("b").exists());
+        assert!(!static_dir.exists());
+        assert!(!manifest_path(&repo, "b").exists());
+        assert!(!fs::read_to_string(crate::related::related_path(&repo)).unwrap().contains("\"b\""));
+        let a = fs::read_to_string(blog_dir(&repo).join("a/+page.md")).unwrap();
+        assert!(a.contains("Part 1 of 2") && a.contains("href=\"/blog/c\">Part C →</a>"));
+        assert!(!a.contains("/blog/b"));
+
+        let leftovers = |dir: PathBuf| fs::read_dir(dir).unwrap().flatten().any(|e| e.file_name().to_string_lossy().ends_with(".deleted"));
+        assert!(!leftovers(blog_dir(&repo)) && !leftovers(static_dir.parent().unwrap().to_path_buf()));
+    }
+
+    #[test]
+    fn only_opening_for_edit_records_a_revision() {
+        let repo = fake_repo("open-for-edit");
+        create(&repo, "post", "Post", "Body");
+        let post_file = blog_dir(&repo).join("post/+page.md");
+        fs::write(&post_file, fs::read_to_string(&post_file).unwrap().replace("Body", "Edited")).unwrap();
+
+        read_post(repo.clone(), "post".into()).unwrap();
+        assert_eq!(list_revisions(repo.clone(), "post".into()).unwrap().len(), 1);
+
+        open_post_for_edit(repo.clone(), "post".into()).unwrap();
+        assert_eq!(list_revisions(repo.clone(), "post".into()).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn toc_ids_are_matched_by_heading_text() {
+        let repo = fake_repo("toc-ids");
+        create(&repo, "post", "Post", "## Intro\n\n## Intro\n\n### Deep dive\n");
+        set_post_toc(repo.clone(), "post".into(), true).unwrap();
+
+        let page = fs::read_to_string(blog_dir(&repo).join("post").join("+page.md")).unwrap();
+        assert!(page.contains(r#"const headingIds = new Set<string>(["intro","intro-1","deep-dive"]);"#));
+        assert!(page.contains("headingIds.has(id)"));
+    }
+
+    #[test]
+    fn listing_entries_are_found_by_slug() {
+        let listing = "    const posts = [\n        {\n            slug: \"a\",\n            content: \"rust, borrowing\",\n        },\n        {\n            slug: \"b\",\n        },\n    ];\n";
+
+        let (start, end) = find_listing_entry(listing, "a").unwrap();
+        let entry = &listing[start..end];
+        assert!(entry.starts_with("        {") && entry.ends_with("},"));
+        assert_eq!(js_field(entry, "slug").as_deref(), Some("a"));
+        assert_eq!(js_field(entry, "content").as_deref(), Some("rust, borrowing"));
+
+        let (start, end) = find_listing_entry(listing, "b").unwrap();
+        assert_eq!(js_field(&listing[start..end], "content"), None);
+        assert_eq!(find_listing_entry(listing, "c"), None);
+    }
+
+    #[test]
+    fn metadata_strings_survive_quotes_backslashes_and_script_tags() {
+        let repo = fake_repo("escape");
+        create(&repo, "plain", "Plain", "Body");
+        let title = "A \"quoted\" </script> title";
+        let excerpt = "Back\\slash,\nnew line";
+        create_blog_post(
+            repo.clone(),
+            title.into(),
+            "a".into(),
+            excerpt.into(),
+            "Body".into(),
+            vec!["tag".into()],
+            "c:\\path, </script>".into(),
+            None,
+        )
+        .unwrap();
+        crate::series::create_series(repo.clone(), "Intro </script>".into(), vec!["a".into()]).unwrap();
+
+        let script_ends = |slug: &str| {
+            let page = fs::read_to_string(blog_dir(&repo).join(slug).join("+page.md")).unwrap();
+            page.matches("</script>").count()
+        };
+        assert_eq!(script_ends("a"), script_ends("plain"));
+        let listing = fs::read_to_string(blog_dir(&repo).join("+page.svelte")).unwrap();
+        assert_eq!(listing.matches("</script>").count(), 1);
+
+        let post = read_post(repo.clone(), "a".into()).unwrap();
+        assert_eq!(post.title, title);
+        assert_eq!(post.excerpt, excerpt);
+        assert_eq!(post.keywords, "c:\\path, </script>");
+        assert_eq!(post.series, "Intro </script>");
+        assert_eq!(listing_keywords(&repo, "a").as_deref(), Some("c:\\path, </script>"));
+    }
+
+    #[test]
+    fn saving_over_an_outside_edit_is_a_conflict() {
+        let repo = fake_repo("conflict");
+        create(&repo, "post", "Post", "Body");
+        let loaded = open_post_for_edit(repo.clone(), "post".into()).unwrap();
+
+        let post_file = blog_dir(&repo).join("post").join("+page.md");
+        let outside = fs::read_to_string(&post_file).unwrap().replace("Body", "Outside body");
+        fs::write(&post_file, &outside).unwrap();
+
+        let save = |version: String| {
+            update_post(repo.clone(), "post".into(), loaded.title.clone(), loaded.excerpt.clone(), "Editor body".into(), loaded.tags.clone(), version)
+        };
+        let Err(AppError::Conflict { version, diff, .. }) = save(loaded.version.clone()) else {
+            panic!("expected a conflict");
+        };
+        assert!(diff.theirs.is_some() && diff.ours.is_some());
+        assert_eq!(fs::read_to_string(&post_file).unwrap(), outside);
+
+        // Saving again with the version the conflict returned overwrites
+        save(version).unwrap();
+        assert_eq!(read_post(repo, "post".into()).unwrap().content, "Editor body");
+    }
+
+    #[test]
+    fn preview_matches_what_saving_writes() {
+        let repo = fake_repo("preview");
+        create(&repo, "old", "Old", "Body");
+
+        // An older post: keywords only in the listing entry
+        let post_file = blog_dir(&repo).join("old").join("+page.md");
+        let page = fs::read_to_string(&post_file).unwrap().replace("keywords: \"keywords\"", "keywords: \"\"");
+        fs::write(&post_file, page).unwrap();
+        let listing_file = blog_dir(&repo).join("+page.svelte");
+        let listing = fs::read_to_string(&listing_file).unwrap().replace("content: \"keywords\"", "content: \"legacy words\"");
+        fs::write(&listing_file, listing).unwrap();
+
+        let preview = |new_post| {
+            render_preview(repo.clone(), "old".into(), "Old".into(), "Excerpt".into(), "Body".into(), vec!["tag".into()], String::new(), Some(new_post))
+        };
+        let template = preview(false).unwrap().template;
+        assert!(template.contains("keywords: \"legacy words\""));
+
+        let post = read_post(repo.clone(), "old".into()).unwrap();
+        update_post(repo.clone(), "old".into(), post.title, post.excerpt, post.content, post.tags, post.version).unwrap();
+        assert_eq!(fs::read_to_string(&post_file).unwrap(), template);
+
+        // A new post cannot take the slug of an existing one
+        assert!(matches!(preview(true), Err(AppError::PostExists { .. })));
+    }
+
+    #[test]
+    fn series_navigation_changes_are_not_conflicts() {
+        let repo = fake_repo("nav-conflict");
+        create(&repo, "a", "Part A", "Body A");
+        create(&repo, "b", "Part B", "Body B");
+        crate::series::create_series(repo.clone(), "Intro".into(), vec!["a".into(), "b".into()]).unwrap();
+        let a = open_post_for_edit(repo.clone(), "a".into()).unwrap();
+
+        // Renaming b rewrites a's navigation; with no history to compare
+        // against, a's version must still match
+        fs::remove_dir_all(crate::config::manager_dir(&repo).join("history")).unwrap();
+        let b = open_post_for_edit(repo.clone(), "b".into()).unwrap();
+        update_post(repo.clone(), "b".into(), "Part Two".into(), b.excerpt, b.content, b.tags, b.version).unwrap();
+        assert!(fs::read_to_string(blog_dir(&repo).join("a").join("+page.md")).unwrap().contains("Part Two"));
+
+        update_post(repo, "a".into(), a.title, a.excerpt, "New body A".into(), a.tags, a.version).unwrap();
+    }
+}
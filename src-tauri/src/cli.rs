@@ -0,0 +1,226 @@
+use crate::bibtex::sync_references;
+use crate::blog::{
+    create_blog_post, delete_post, load_posts, open_post_for_edit, read_post, set_post_toc, slugify, update_post,
+};
+use crate::error::AppError;
+use crate::feeds::generate_feeds;
+use crate::lint::{lint_post, Severity};
+use crate::related::update_related_posts;
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+use serde_json::json;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+
+/// Manage the blog from the command line. Results are printed to stdout as
+/// JSON; errors go to stderr as `{ code, message, path }`.
+#[derive(Parser)]
+#[command(name = "blog", version)]
+struct Cli {
+    /// Path to the website repo
+    #[arg(long, global = true, default_value = ".")]
+    repo: String,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create a post
+    New {
+        #[arg(long)]
+        title: String,
+        /// Defaults to the slugified title
+        #[arg(long)]
+        slug: Option<String>,
+        /// Generated from the content if empty
+        #[arg(long, default_value = "")]
+        excerpt: String,
+        #[command(flatten)]
+        content: ContentArgs,
+        /// Comma-separated
+        #[arg(long, value_delimiter = ',')]
+        tags: Vec<String>,
+        /// Generated from the content if empty
+        #[arg(long, default_value = "")]
+        keywords: String,
+        /// Draft on a `post/<slug>` branch
+        #[arg(long)]
+        branch: bool,
+    },
+    /// List all posts
+    List,
+    /// Print a post
+    Show { slug: String },
+    /// Change a post; anything not given keeps its current value
+    Update {
+        slug: String,
+        #[arg(long)]
+        title: Option<String>,
+        #[arg(long)]
+        excerpt: Option<String>,
+        #[command(flatten)]
+        content: ContentArgs,
+        /// Comma-separated
+        #[arg(long, value_delimiter = ',')]
+        tags: Option<Vec<String>>,
+        /// Refuse to save if the post changed since `show` returned this version
+        #[arg(long)]
+        version: Option<String>,
+    },
+    /// Delete a post
+    Delete { slug: String },
+    /// Turn a post's table of contents on, or off with `--off`
+    Toc {
+        slug: String,
+        #[arg(long)]
+        off: bool,
+    },
+    /// Regenerate references.ts from references.bib
+    SyncRefs,
+    /// Lint one post, or all of them. Exits with 1 if there are errors.
+    Lint { slug: Option<String> },
+    /// Write the RSS and Atom feeds
+    Feeds,
+    /// Recompute the related posts in related.ts
+    Related,
+}
+
+#[derive(clap::Args)]
+struct ContentArgs {
+    /// Markdown body; `-` reads it from stdin
+    #[arg(long, conflicts_with = "content_file")]
+    content: Option<String>,
+    /// Read the Markdown body from a file
+    #[arg(long)]
+    content_file: Option<PathBuf>,
+}
+
+impl ContentArgs {
+    fn read(self) -> Result<Option<String>, AppError> {
+        match (self.content, self.content_file) {
+            (Some(content), _) if content == "-" => {
+                let mut content = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut content)
+                    .map_err(|e| AppError::io("read stdin", &PathBuf::from("-"), e))?;
+                Ok(Some(content))
+            }
+            (Some(content), _) => Ok(Some(content)),
+            (None, Some(path)) => fs::read_to_string(&path)
+                .map(Some)
+                .map_err(|e| AppError::io("read content file", &path, e)),
+            (None, None) => Ok(None),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct PostSummary {
+    slug: String,
+    title: String,
+    date: String,
+    updated: String,
+    excerpt: String,
+    tags: Vec<String>,
+    word_count: usize,
+}
+
+/// Run the command and the JSON to print, plus whether it succeeded
+fn execute(cli: Cli) -> Result<(serde_json::Value, bool), AppError> {
+    let repo = cli.repo;
+    let value = match cli.command {
+        Command::New { title, slug, excerpt, content, tags, keywords, branch } => {
+            let slug = slug.unwrap_or_else(|| slugify(title.clone()));
+            let content = content.read()?.unwrap_or_default();
+            let message = create_blog_post(repo, title, slug.clone(), excerpt, content, tags, keywords, Some(branch))?;
+            json!({ "slug": slug, "message": message })
+        }
+        Command::List => {
+            let posts: Vec<PostSummary> = load_posts(&repo)?
+                .into_iter()
+                .map(|p| PostSummary {
+                    slug: p.slug,
+                    title: p.title,
+                    date: p.date,
+                    updated: p.updated,
+                    excerpt: p.excerpt,
+                    tags: p.tags,
+                    word_count: p.word_count,
+                })
+                .collect();
+            json!(posts)
+        }
+        // Both keep the version they read in the history, for `update --version`
+        Command::Show { slug } => json!(open_post_for_edit(repo, slug)?),
+        Command::Update { slug, title, excerpt, content, tags, version } => {
+            let current = open_post_for_edit(repo.clone(), slug.clone())?;
+            let content = content.read()?.unwrap_or(current.content);
+            let message = update_post(
+                repo,
+                slug.clone(),
+                title.unwrap_or(current.title),
+                excerpt.unwrap_or(current.excerpt),
+                content,
+                tags.unwrap_or(current.tags),
+                version.unwrap_or(current.version),
+            )?;
+            json!({ "slug": slug, "message": message })
+        }
+        Command::Delete { slug } => {
+            let message = delete_post(repo, slug.clone())?;
+            json!({ "slug": slug, "message": message })
+        }
+        Command::Toc { slug, off } => {
+            let message = set_post_toc(repo, slug.clone(), !off)?;
+            json!({ "slug": slug, "message": message })
+        }
+        Command::SyncRefs => json!(sync_references(repo)?),
+        Command::Lint { slug } => {
+            let posts = match slug {
+                Some(slug) => vec![read_post(repo.clone(), slug)?],
+                None => load_posts(&repo)?,
+            };
+
+            let mut clean = true;
+            let mut results = Vec::new();
+            for post in posts {
+                let issues = lint_post(repo.clone(), post.content)?;
+                clean &= !issues.iter().any(|i| i.severity == Severity::Error);
+                results.push(json!({ "slug": post.slug, "issues": issues }));
+            }
+            return Ok((json!(results), clean));
+        }
+        Command::Feeds => json!({ "files": generate_feeds(repo)? }),
+        Command::Related => json!({ "message": update_related_posts(repo)? }),
+    };
+    Ok((value, true))
+}
+
+/// Entry point of the `blog` binary; returns the process exit code
+pub fn run() -> i32 {
+    let cli = match Cli::try_parse() {
+        Ok(cli) => cli,
+        Err(e) => {
+            let _ = e.print();
+            return e.exit_code();
+        }
+    };
+
+    match execute(cli) {
+        Ok((value, success)) => {
+            // Ignore a closed pipe (e.g. output piped into `head`)
+            let _ = writeln!(std::io::stdout(), "{}", serde_json::to_string_pretty(&value).unwrap_or_default());
+            if success {
+                0
+            } else {
+                1
+            }
+        }
+        Err(e) => {
+            eprintln!("{}", serde_json::to_string_pretty(&e).unwrap_or_else(|_| e.to_string()));
+            1
+        }
+    }
+}
@@ -0,0 +1,227 @@
+use crate::error::AppError;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Per-repository manager settings, stored in `.blog-manager/config.json`
+/// inside the website repo. Missing keys fall back to their defaults.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct BlogConfig {
+    /// Public URL of the deployed site (e.g. `https://example.com`), used for
+    /// absolute links in meta tags
+    pub site_url: String,
+    pub site_name: String,
+    /// Author named in structured data (omitted when empty)
+    pub author: String,
+    /// Maximum length (in characters) of auto-generated excerpts
+    pub excerpt_length: usize,
+    /// Number of auto-generated keywords
+    pub keyword_count: usize,
+    /// Number of related posts listed under each post
+    pub related_count: usize,
+    pub lint: LintConfig,
+    pub external_links: ExternalLinkConfig,
+    pub assets: AssetConfig,
+    pub history: HistoryConfig,
+}
+
+impl Default for BlogConfig {
+    fn default() -> Self {
+        BlogConfig {
+            site_url: String::new(),
+            site_name: "Mr. Wong's Blog".to_string(),
+            author: String::new(),
+            excerpt_length: 160,
+            keyword_count: 8,
+            related_count: 3,
+            lint: LintConfig::default(),
+            external_links: ExternalLinkConfig::default(),
+            assets: AssetConfig::default(),
+            history: HistoryConfig::default(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct LintConfig {
+    /// Rule ids (e.g. `"trailing-whitespace"`) that `lint_post` should skip
+    pub disabled_rules: Vec<String>,
+    /// Word count above which a paragraph is reported as too long
+    pub max_paragraph_words: usize,
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        LintConfig {
+            disabled_rules: Vec::new(),
+            max_paragraph_words: 150,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ExternalLinkConfig {
+    /// Maximum number of requests in flight at once
+    pub concurrency: usize,
+    pub timeout_secs: u64,
+    /// Extra attempts after a timeout, connection error, 429 or 5xx
+    pub retries: u32,
+    /// Minimum delay between two requests to the same host
+    pub per_host_delay_ms: u64,
+    /// How long a cached result is reused before the URL is checked again
+    pub cache_ttl_hours: u64,
+    pub user_agent: String,
+}
+
+impl Default for ExternalLinkConfig {
+    fn default() -> Self {
+        ExternalLinkConfig {
+            concurrency: 8,
+            timeout_secs: 10,
+            retries: 2,
+            per_host_delay_ms: 1000,
+            cache_ttl_hours: 24,
+            user_agent: "BlogManager-LinkChecker/0.1".to_string(),
+        }
+    }
+}
+
+/// Where imported assets are stored
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AssetLocation {
+    /// `static/blog/<slug>/`, served at `/blog/<slug>/<file>`
+    #[default]
+    Static,
+    /// Next to `+page.md`, linked relatively; needs the site to resolve
+    /// post-relative assets
+    Post,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct AssetConfig {
+    pub location: AssetLocation,
+    pub images: ImageConfig,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum VariantFormat {
+    Webp,
+    Avif,
+}
+
+/// Processing applied to imported raster images (JPEG, PNG, WebP)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ImageConfig {
+    /// Generate resized variants and a `<picture>` snippet
+    pub responsive: bool,
+    /// Target widths in pixels; widths above the original are skipped
+    pub widths: Vec<u32>,
+    /// Variant formats. AVIF is lossy and small; WebP variants are lossless,
+    /// so they are only worth adding for PNG-like sources. Either way a variant
+    /// that is not smaller than the imported file is skipped.
+    pub formats: Vec<VariantFormat>,
+    /// AVIF quality (1-100) and encoder speed (1 slowest - 10 fastest)
+    pub avif_quality: u8,
+    pub avif_speed: u8,
+    /// Also keep the full-size original (with metadata stripped)
+    pub keep_original: bool,
+    /// `sizes` attribute of the generated `<source>` elements
+    pub sizes: String,
+}
+
+impl Default for ImageConfig {
+    fn default() -> Self {
+        ImageConfig {
+            responsive: true,
+            widths: vec![480, 960, 1600],
+            formats: vec![VariantFormat::Avif],
+            avif_quality: 70,
+            avif_speed: 8,
+            keep_original: true,
+            sizes: "(max-width: 800px) 100vw, 800px".to_string(),
+        }
+    }
+}
+
+/// Retention for post revision snapshots. The newest revision is always kept.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct HistoryConfig {
+    /// Revisions kept per post (0 = unlimited)
+    pub max_revisions: usize,
+    /// Revisions older than this are pruned (0 = keep forever)
+    pub max_age_days: u64,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        HistoryConfig {
+            max_revisions: 50,
+            max_age_days: 0,
+        }
+    }
+}
+
+/// Keeps the manager's local state (history, caches) out of the website's
+/// commits; only the config is worth sharing between clones
+const MANAGER_GITIGNORE: &str = "# Local state of the blog manager\n*\n!.gitignore\n!config.json\n";
+
+/// Directory for manager-owned state inside the website repo
+pub(crate) fn manager_dir(repo_path: &str) -> PathBuf {
+    Path::new(repo_path).join(".blog-manager")
+}
+
+/// Create the manager directory, with its `.gitignore`, if it does not exist
+pub(crate) fn ensure_manager_dir(repo_path: &str) -> Result<PathBuf, AppError> {
+    let dir = manager_dir(repo_path);
+    fs::create_dir_all(&dir).map_err(|e| AppError::io("create manager directory", &dir, e))?;
+
+    let gitignore = dir.join(".gitignore");
+    if !gitignore.exists() {
+        fs::write(&gitignore, MANAGER_GITIGNORE).map_err(|e| AppError::io("write", &gitignore, e))?;
+    }
+    Ok(dir)
+}
+
+fn config_path(repo_path: &str) -> PathBuf {
+    manager_dir(repo_path).join("config.json")
+}
+
+/// Load the repo's config, or the defaults if none has been saved yet
+pub(crate) fn load_config(repo_path: &str) -> Result<BlogConfig, AppError> {
+    let path = config_path(repo_path);
+    if !path.exists() {
+        return Ok(BlogConfig::default());
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| AppError::io("read config", &path, e))?;
+
+    serde_json::from_str(&content)
+        .map_err(|e| AppError::InvalidConfig { path, reason: e.to_string() })
+}
+
+#[tauri::command]
+pub fn get_config(repo_path: String) -> Result<BlogConfig, AppError> {
+    load_config(&repo_path)
+}
+
+#[tauri::command]
+pub fn save_config(repo_path: String, config: BlogConfig) -> Result<(), AppError> {
+    ensure_manager_dir(&repo_path)?;
+    let path = config_path(&repo_path);
+
+    let content = serde_json::to_string_pretty(&config).map_err(|e| AppError::Other {
+        message: format!("Failed to serialize config: {}", e),
+    })?;
+
+    fs::write(&path, content)
+        .map_err(|e| AppError::io("write config", &path, e))
+}
@@ -0,0 +1,253 @@
+use crate::blog::{read_post, PostContent};
+use crate::error::AppError;
+use crate::history::read_revision;
+use serde::{Deserialize, Serialize};
+use similar::{ChangeTag, TextDiff};
+
+/// Unchanged lines shown around each change
+const CONTEXT_LINES: usize = 3;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Equal,
+    Insert,
+    Delete,
+}
+
+impl From<ChangeTag> for ChangeKind {
+    fn from(tag: ChangeTag) -> Self {
+        match tag {
+            ChangeTag::Equal => ChangeKind::Equal,
+            ChangeTag::Insert => ChangeKind::Insert,
+            ChangeTag::Delete => ChangeKind::Delete,
+        }
+    }
+}
+
+/// A run of text that was kept, added or removed
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WordChange {
+    pub kind: ChangeKind,
+    pub text: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DiffLine {
+    pub kind: ChangeKind,
+    /// 1-based line numbers on each side (absent for inserted/deleted lines)
+    pub old_line: Option<usize>,
+    pub new_line: Option<usize>,
+    pub content: String,
+    /// Word-level breakdown of a changed line; `Insert`/`Delete` runs mark
+    /// the words that differ from the paired line on the other side
+    pub words: Vec<WordChange>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DiffHunk {
+    pub old_start: usize,
+    pub old_lines: usize,
+    pub new_start: usize,
+    pub new_lines: usize,
+    pub lines: Vec<DiffLine>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FieldDiff {
+    pub field: String,
+    pub old: String,
+    pub new: String,
+    pub words: Vec<WordChange>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PostDiff {
+    /// Only metadata fields that changed
+    pub fields: Vec<FieldDiff>,
+    pub hunks: Vec<DiffHunk>,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+}
+
+/// Push text onto `words`, merging it into the last run if the kind matches
+fn push_word(words: &mut Vec<WordChange>, kind: ChangeKind, text: &str) {
+    match words.last_mut() {
+        Some(last) if last.kind == kind => last.text.push_str(text),
+        _ => words.push(WordChange {
+            kind,
+            text: text.to_string(),
+        }),
+    }
+}
+
+/// One run per tag, so a renamed tag shows as a removal plus an addition
+fn tag_diff(old: &[String], new: &[String]) -> Vec<WordChange> {
+    let old: Vec<&str> = old.iter().map(String::as_str).collect();
+    let new: Vec<&str> = new.iter().map(String::as_str).collect();
+
+    TextDiff::from_slices(&old, &new)
+        .iter_all_changes()
+        .map(|change| WordChange {
+            kind: change.tag().into(),
+            text: change.value().to_string(),
+        })
+        .collect()
+}
+
+fn word_diff(old: &str, new: &str) -> Vec<WordChange> {
+    let mut words = Vec::new();
+    for change in TextDiff::from_words(old, new).iter_all_changes() {
+        push_word(&mut words, change.tag().into(), change.value());
+    }
+    words
+}
+
+/// Line hunks (with word-level detail) between two Markdown bodies
+pub(crate) fn diff_lines(old: &str, new: &str) -> Vec<DiffHunk> {
+    // Bodies are stored trimmed; without a final newline an edit after the
+    // last line would also show that line as changed
+    let old = format!("{}\n", old.trim_end_matches('\n'));
+    let new = format!("{}\n", new.trim_end_matches('\n'));
+    let diff = TextDiff::from_lines(&old, &new);
+
+    diff.grouped_ops(CONTEXT_LINES)
+        .iter()
+        .map(|group| {
+            let (first, last) = (&group[0], &group[group.len() - 1]);
+            let old_range = first.old_range().start..last.old_range().end;
+            let new_range = first.new_range().start..last.new_range().end;
+
+            let mut lines = Vec::new();
+            for op in group {
+                for change in diff.iter_inline_changes(op) {
+                    let kind = ChangeKind::from(change.tag());
+                    let mut content = String::new();
+                    let mut words = Vec::new();
+                    for (emphasized, text) in change.iter_strings_lossy() {
+                        let text = text.trim_end_matches('\n');
+                        content.push_str(text);
+                        if kind != ChangeKind::Equal {
+                            push_word(&mut words, if emphasized { kind } else { ChangeKind::Equal }, text);
+                        }
+                    }
+
+                    lines.push(DiffLine {
+                        kind,
+                        old_line: change.old_index().map(|i| i + 1),
+                        new_line: change.new_index().map(|i| i + 1),
+                        content,
+                        words,
+                    });
+                }
+            }
+
+            DiffHunk {
+                old_start: old_range.start + 1,
+                old_lines: old_range.len(),
+                new_start: new_range.start + 1,
+                new_lines: new_range.len(),
+                lines,
+            }
+        })
+        .collect()
+}
+
+/// What happened to a post between the version an editor loaded and now
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ThreeWayDiff {
+    /// Loaded version -> file on disk; `None` if the loaded version is no
+    /// longer in the history
+    pub theirs: Option<PostDiff>,
+    /// Loaded version -> editor
+    pub ours: Option<PostDiff>,
+    /// File on disk -> editor, i.e. what saving anyway would change
+    pub overwrite: PostDiff,
+}
+
+pub(crate) fn three_way_diff(base: Option<&PostContent>, disk: &PostContent, editor: &PostContent) -> ThreeWayDiff {
+    ThreeWayDiff {
+        theirs: base.map(|base| diff_posts(base, disk)),
+        ours: base.map(|base| diff_posts(base, editor)),
+        overwrite: diff_posts(disk, editor),
+    }
+}
+
+/// Compare two versions of a post field by field and line by line
+pub(crate) fn diff_posts(old: &PostContent, new: &PostContent) -> PostDiff {
+    let mut fields: Vec<FieldDiff> = [
+        ("title", &old.title, &new.title),
+        ("date", &old.date, &new.date),
+        ("updated", &old.updated, &new.updated),
+        ("excerpt", &old.excerpt, &new.excerpt),
+        ("keywords", &old.keywords, &new.keywords),
+        ("image", &old.image, &new.image),
+    ]
+    .into_iter()
+    .filter(|(_, old, new)| old != new)
+    .map(|(field, old, new)| FieldDiff {
+        field: field.to_string(),
+        old: old.clone(),
+        new: new.clone(),
+        words: word_diff(old, new),
+    })
+    .collect();
+
+    if old.tags != new.tags {
+        fields.push(FieldDiff {
+            field: "tags".to_string(),
+            old: old.tags.join(", "),
+            new: new.tags.join(", "),
+            words: tag_diff(&old.tags, &new.tags),
+        });
+    }
+
+    let hunks = diff_lines(&old.content, &new.content);
+    let count = |kind: ChangeKind| {
+        hunks
+            .iter()
+            .flat_map(|h| &h.lines)
+            .filter(|l| l.kind == kind)
+            .count()
+    };
+
+    PostDiff {
+        fields,
+        lines_added: count(ChangeKind::Insert),
+        lines_removed: count(ChangeKind::Delete),
+        hunks,
+    }
+}
+
+/// Diff two revisions of a post. Without `to`, compares against the post as
+/// currently saved.
+#[tauri::command]
+pub fn diff_post(repo_path: String, slug: String, from: String, to: Option<String>) -> Result<PostDiff, AppError> {
+    let old = read_revision(repo_path.clone(), slug.clone(), from)?;
+    let new = match to {
+        Some(id) => read_revision(repo_path, slug, id)?,
+        None => read_post(repo_path, slug)?,
+    };
+    Ok(diff_posts(&old, &new))
+}
+
+/// Diff the saved post against the editor's unsaved values
+#[tauri::command]
+pub fn diff_post_buffer(
+    repo_path: String,
+    slug: String,
+    title: String,
+    excerpt: String,
+    content: String,
+    tags: Vec<String>,
+) -> Result<PostDiff, AppError> {
+    let saved = read_post(repo_path, slug)?;
+    let buffer = PostContent {
+        title,
+        excerpt,
+        content,
+        tags,
+        ..saved.clone()
+    };
+    Ok(diff_posts(&saved, &buffer))
+}
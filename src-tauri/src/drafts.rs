@@ -0,0 +1,241 @@
+use crate::assets::hash_bytes;
+use crate::blog::{read_post, PostContent};
+use crate::diff::{diff_posts, PostDiff};
+use crate::error::AppError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+/// Minimum time between two writes of the same draft
+const THROTTLE: Duration = Duration::from_secs(2);
+
+/// An unsaved editor buffer
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Draft {
+    /// Slug of the post being edited; `None` for the new post form
+    pub editing: Option<String>,
+    pub slug: String,
+    pub title: String,
+    pub excerpt: String,
+    pub content: String,
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub keywords: String,
+    /// Filled in by the backend
+    #[serde(default)]
+    pub repo_path: String,
+    /// Unix timestamp of the last autosave, filled in by the backend
+    #[serde(default)]
+    pub saved_at: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RecoverableDraft {
+    pub draft: Draft,
+    pub age_secs: i64,
+    /// Changes against the saved post (only for drafts of existing posts)
+    pub diff: Option<PostDiff>,
+}
+
+type Pending = HashMap<PathBuf, Draft>;
+
+/// Drafts waiting for their throttled write, keyed by file path
+#[derive(Default)]
+pub struct DraftStore {
+    pending: Arc<Mutex<Pending>>,
+}
+
+/// Fails, rather than panics, if a thread panicked while holding the lock
+fn lock_pending(pending: &Mutex<Pending>) -> Result<MutexGuard<'_, Pending>, AppError> {
+    pending.lock().map_err(|_| AppError::Other {
+        message: "Autosave stopped after an earlier failure; restart the app".to_string(),
+    })
+}
+
+fn drafts_dir(app: &AppHandle) -> Result<PathBuf, AppError> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join("drafts"))
+        .map_err(|e| AppError::Other {
+            message: format!("Failed to locate app data directory: {}", e),
+        })
+}
+
+/// One file per repo and editor: the new post form, or each edited post
+fn draft_path(dir: &Path, repo_path: &str, editing: Option<&str>) -> PathBuf {
+    let key = format!("{}\n{}", repo_path, editing.unwrap_or(""));
+    dir.join(format!("{}.json", &hash_bytes(key.as_bytes())[..16]))
+}
+
+/// Write via a temporary file so a crash mid-write never leaves a torn draft
+fn write_draft(path: &Path, draft: &Draft) -> Result<(), AppError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| AppError::io("create drafts directory", parent, e))?;
+    }
+
+    let content = serde_json::to_string(draft).map_err(|e| AppError::Other {
+        message: format!("Failed to serialize draft: {}", e),
+    })?;
+    let temp = path.with_extension("json.tmp");
+    fs::write(&temp, content)
+        .and_then(|_| fs::rename(&temp, path))
+        .map_err(|e| AppError::io("write draft", path, e))
+}
+
+fn load_drafts(dir: &Path) -> Vec<(PathBuf, Draft)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|e| e == "json"))
+        .filter_map(|path| {
+            let draft = fs::read_to_string(&path)
+                .ok()
+                .and_then(|content| serde_json::from_str(&content).ok())?;
+            Some((path, draft))
+        })
+        .collect()
+}
+
+impl DraftStore {
+    /// Queue `draft` for writing. The first call for a file schedules a write
+    /// after `THROTTLE`; later calls before then only replace what is written.
+    /// Whatever is still queued when the editor loses focus is written by
+    /// `flush`.
+    pub(crate) fn save(&self, path: PathBuf, draft: Draft) -> Result<(), AppError> {
+        let mut pending = lock_pending(&self.pending)?;
+        if pending.insert(path.clone(), draft).is_some() {
+            return Ok(());
+        }
+
+        let pending = Arc::clone(&self.pending);
+        thread::spawn(move || {
+            thread::sleep(THROTTLE);
+            // Write under the lock so a discard cannot race with the write
+            let result = lock_pending(&pending).and_then(|mut pending| match pending.remove(&path) {
+                Some(draft) => write_draft(&path, &draft),
+                None => Ok(()),
+            });
+            if let Err(e) = result {
+                log::error!("Autosave failed: {}", e);
+            }
+        });
+        Ok(())
+    }
+
+    /// Write every queued draft now, returning the first failure
+    pub(crate) fn flush(&self) -> Result<(), AppError> {
+        let mut pending = lock_pending(&self.pending)?;
+        let mut result = Ok(());
+        for (path, draft) in pending.drain() {
+            if let Err(e) = write_draft(&path, &draft) {
+                log::error!("Autosave failed: {}", e);
+                result = result.and(Err(e));
+            }
+        }
+        result
+    }
+
+    /// Drop any queued write and delete the saved draft
+    pub(crate) fn discard(&self, path: &Path) -> Result<(), AppError> {
+        lock_pending(&self.pending)?.remove(path);
+        match fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(AppError::io("delete draft", path, e))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Drafts saved for `repo_path`. Drafts of existing posts come with a diff
+/// against the saved file; those that no longer differ are deleted.
+pub(crate) fn recoverable_drafts(dir: &Path, repo_path: &str) -> Vec<RecoverableDraft> {
+    let now = chrono::Utc::now().timestamp();
+    let mut drafts = Vec::new();
+
+    for (path, draft) in load_drafts(dir) {
+        if draft.repo_path != repo_path {
+            continue;
+        }
+
+        let saved = draft
+            .editing
+            .as_ref()
+            .and_then(|slug| read_post(repo_path.to_string(), slug.clone()).ok());
+        let diff = saved.map(|saved| {
+            let buffer = PostContent {
+                title: draft.title.clone(),
+                excerpt: draft.excerpt.clone(),
+                content: draft.content.clone(),
+                tags: draft.tags.clone(),
+                ..saved.clone()
+            };
+            diff_posts(&saved, &buffer)
+        });
+
+        if diff.as_ref().is_some_and(|d| d.fields.is_empty() && d.hunks.is_empty()) {
+            let _ = fs::remove_file(&path);
+            continue;
+        }
+
+        drafts.push(RecoverableDraft {
+            age_secs: now - draft.saved_at,
+            draft,
+            diff,
+        });
+    }
+
+    drafts.sort_by_key(|d| d.age_secs);
+    drafts
+}
+
+/// Persist the in-progress editor buffer (throttled) so it survives a crash
+#[tauri::command]
+pub fn autosave_draft(
+    app: AppHandle,
+    store: State<'_, DraftStore>,
+    repo_path: String,
+    draft: Draft,
+) -> Result<(), AppError> {
+    let path = draft_path(&drafts_dir(&app)?, &repo_path, draft.editing.as_deref());
+    let draft = Draft {
+        repo_path,
+        saved_at: chrono::Utc::now().timestamp(),
+        ..draft
+    };
+    store.save(path, draft)
+}
+
+/// Write the queued drafts without waiting, e.g. when the window loses focus
+/// or closes
+#[tauri::command]
+pub fn flush_drafts(store: State<'_, DraftStore>) -> Result<(), AppError> {
+    store.flush()
+}
+
+/// Forget the draft for an editor, e.g. after the post was saved
+#[tauri::command]
+pub fn discard_draft(
+    app: AppHandle,
+    store: State<'_, DraftStore>,
+    repo_path: String,
+    editing: Option<String>,
+) -> Result<(), AppError> {
+    let path = draft_path(&drafts_dir(&app)?, &repo_path, editing.as_deref());
+    store.discard(&path)
+}
+
+/// Unsaved drafts for the repo, newest first
+#[tauri::command]
+pub fn recover_drafts(app: AppHandle, repo_path: String) -> Result<Vec<RecoverableDraft>, AppError> {
+    Ok(recoverable_drafts(&drafts_dir(&app)?, &repo_path))
+}
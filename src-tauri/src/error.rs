@@ -0,0 +1,160 @@
+use crate::diff::ThreeWayDiff;
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Error returned by the app's commands. It reaches the UI as
+/// `{ code, message, path, ... }`, where `code` is stable and can be matched
+/// on and `message` is meant to be shown as is.
+#[derive(Debug)]
+pub enum AppError {
+    PostExists {
+        slug: String,
+        path: PathBuf,
+    },
+    PostNotFound {
+        slug: String,
+        path: PathBuf,
+    },
+    BlogDirNotFound {
+        path: PathBuf,
+    },
+    /// The listing has no `const posts = [` to insert new posts after
+    ListingMarkerMissing {
+        path: PathBuf,
+    },
+    BibNotFound {
+        path: PathBuf,
+    },
+    /// A post file the app cannot make sense of
+    InvalidPost {
+        path: PathBuf,
+        reason: String,
+    },
+    /// `.blog-manager/config.json` exists but cannot be parsed
+    InvalidConfig {
+        path: PathBuf,
+        reason: String,
+    },
+    /// Arguments the command cannot act on, e.g. an invalid series name
+    InvalidInput {
+        message: String,
+    },
+    /// The post changed on disk since the editor loaded it. Saving again
+    /// with `version` overwrites the outside changes.
+    Conflict {
+        slug: String,
+        path: PathBuf,
+        version: String,
+        diff: Box<ThreeWayDiff>,
+    },
+    Io {
+        /// What was being done, e.g. "read blog listing"
+        action: String,
+        path: PathBuf,
+        message: String,
+    },
+    /// A git operation failed (no repository, branch exists, ...)
+    Git {
+        message: String,
+    },
+    /// Anything else, e.g. a social card that failed to render
+    Other {
+        message: String,
+    },
+}
+
+impl AppError {
+    pub(crate) fn io(action: &str, path: &Path, error: std::io::Error) -> Self {
+        AppError::Io {
+            action: action.to_string(),
+            path: path.to_path_buf(),
+            message: error.to_string(),
+        }
+    }
+
+    pub(crate) fn invalid(message: impl Into<String>) -> Self {
+        AppError::InvalidInput { message: message.into() }
+    }
+
+    pub(crate) fn git(message: impl Into<String>) -> Self {
+        AppError::Git { message: message.into() }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::PostExists { .. } => "post_exists",
+            AppError::PostNotFound { .. } => "post_not_found",
+            AppError::BlogDirNotFound { .. } => "blog_dir_not_found",
+            AppError::ListingMarkerMissing { .. } => "listing_marker_missing",
+            AppError::BibNotFound { .. } => "bib_not_found",
+            AppError::InvalidPost { .. } => "invalid_post",
+            AppError::InvalidConfig { .. } => "invalid_config",
+            AppError::InvalidInput { .. } => "invalid_input",
+            AppError::Conflict { .. } => "conflict",
+            AppError::Io { .. } => "io",
+            AppError::Git { .. } => "git",
+            AppError::Other { .. } => "other",
+        }
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            AppError::PostExists { path, .. }
+            | AppError::PostNotFound { path, .. }
+            | AppError::BlogDirNotFound { path }
+            | AppError::ListingMarkerMissing { path }
+            | AppError::BibNotFound { path }
+            | AppError::InvalidPost { path, .. }
+            | AppError::InvalidConfig { path, .. }
+            | AppError::Conflict { path, .. }
+            | AppError::Io { path, .. } => Some(path),
+            AppError::InvalidInput { .. } | AppError::Git { .. } | AppError::Other { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::PostExists { slug, .. } => write!(f, "Post directory already exists: {}", slug),
+            AppError::PostNotFound { slug, .. } => write!(f, "Post not found: {}", slug),
+            AppError::BlogDirNotFound { .. } => write!(f, "Blog directory not found"),
+            AppError::ListingMarkerMissing { .. } => write!(f, "Could not find posts array in blog listing"),
+            AppError::BibNotFound { .. } => write!(f, "references.bib not found"),
+            AppError::InvalidPost { reason, .. } => write!(f, "{}", reason),
+            AppError::InvalidConfig { reason, .. } => write!(f, "Invalid config file: {}", reason),
+            AppError::Conflict { slug, .. } => {
+                write!(f, "{} was changed outside the editor since it was opened", slug)
+            }
+            AppError::Io { action, message, .. } => write!(f, "Failed to {}: {}", action, message),
+            AppError::InvalidInput { message } | AppError::Git { message } | AppError::Other { message } => {
+                write!(f, "{}", message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("code", self.code())?;
+        map.serialize_entry("message", &self.to_string())?;
+        map.serialize_entry("path", &self.path().map(|p| p.to_string_lossy()))?;
+
+        match self {
+            AppError::PostExists { slug, .. } | AppError::PostNotFound { slug, .. } => {
+                map.serialize_entry("slug", slug)?;
+            }
+            AppError::Conflict { slug, version, diff, .. } => {
+                map.serialize_entry("slug", slug)?;
+                map.serialize_entry("version", version)?;
+                map.serialize_entry("diff", diff)?;
+            }
+            _ => {}
+        }
+        map.end()
+    }
+}
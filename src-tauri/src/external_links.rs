@@ -0,0 +1,423 @@
+use crate::bibtex::read_bib_file;
+use crate::blog::load_posts;
+use crate::config::{ensure_manager_dir, load_config, manager_dir, ExternalLinkConfig};
+use crate::error::AppError;
+use crate::markdown::{link_targets, LineIndex};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Mutex, Semaphore};
+use tokio::task::JoinSet;
+use tokio::time::{sleep, sleep_until, Instant};
+
+const RETRY_BACKOFF: Duration = Duration::from_millis(500);
+
+/// A request that got no response, and whether trying again could help
+struct RequestError {
+    message: String,
+    transient: bool,
+}
+
+impl From<reqwest::Error> for RequestError {
+    /// Timeouts and dropped connections are worth retrying; DNS, TLS and
+    /// invalid URLs fail the same way every time
+    fn from(error: reqwest::Error) -> Self {
+        let mut source: Option<&dyn std::error::Error> = Some(&error);
+        let mut reset = false;
+        while let Some(e) = source {
+            if let Some(io) = e.downcast_ref::<std::io::Error>() {
+                reset |= matches!(
+                    io.kind(),
+                    ErrorKind::ConnectionReset | ErrorKind::ConnectionAborted | ErrorKind::BrokenPipe | ErrorKind::TimedOut
+                );
+            }
+            source = e.source();
+        }
+
+        RequestError {
+            transient: error.is_timeout() || reset,
+            message: error.to_string(),
+        }
+    }
+}
+
+/// Where an external URL was found
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum LinkSource {
+    Post { slug: String, line: usize },
+    Reference { key: String },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LinkStatus {
+    pub ok: bool,
+    /// Final HTTP status, if a response was received
+    pub status: Option<u16>,
+    pub error: Option<String>,
+    /// Unix timestamp of the check
+    pub checked_at: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExternalLinkResult {
+    pub url: String,
+    #[serde(flatten)]
+    pub status: LinkStatus,
+    /// Whether the status came from the cache rather than a fresh request
+    pub cached: bool,
+    pub sources: Vec<LinkSource>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExternalLinkReport {
+    pub links_checked: usize,
+    pub broken: usize,
+    /// Broken links first, then alphabetical
+    pub results: Vec<ExternalLinkResult>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CheckerOptions {
+    pub concurrency: usize,
+    pub retries: u32,
+    pub per_host_delay: Duration,
+}
+
+impl From<&ExternalLinkConfig> for CheckerOptions {
+    fn from(config: &ExternalLinkConfig) -> Self {
+        CheckerOptions {
+            concurrency: config.concurrency,
+            retries: config.retries,
+            per_host_delay: Duration::from_millis(config.per_host_delay_ms),
+        }
+    }
+}
+
+/// Checks URLs with bounded concurrency, retries and per-host rate limiting.
+///
+/// The HTTP client is supplied by the caller, so timeouts, redirects, user
+/// agent and proxies are all configured there (and tests can point it at a
+/// local server).
+pub struct ExternalLinkChecker {
+    client: reqwest::Client,
+    options: CheckerOptions,
+    permits: Semaphore,
+    /// Earliest time the next request to each host may start
+    next_slot: Mutex<HashMap<String, Instant>>,
+}
+
+impl ExternalLinkChecker {
+    pub fn new(client: reqwest::Client, options: CheckerOptions) -> Self {
+        ExternalLinkChecker {
+            client,
+            permits: Semaphore::new(options.concurrency.max(1)),
+            options,
+            next_slot: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Reserve the next request slot for the URL's host and wait for it
+    async fn wait_for_host(&self, url: &str) {
+        let Some(host) = reqwest::Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_string))
+        else {
+            return;
+        };
+
+        let slot = {
+            let mut slots = self.next_slot.lock().await;
+            let now = Instant::now();
+            let slot = slots.get(&host).copied().filter(|t| *t > now).unwrap_or(now);
+            slots.insert(host, slot + self.options.per_host_delay);
+            slot
+        };
+        sleep_until(slot).await;
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder, url: &str) -> Result<u16, RequestError> {
+        self.wait_for_host(url).await;
+        let _permit = self.permits.acquire().await.map_err(|e| RequestError {
+            message: e.to_string(),
+            transient: false,
+        })?;
+        let response = request.send().await?;
+        Ok(response.status().as_u16())
+    }
+
+    async fn request(&self, url: &str) -> Result<u16, RequestError> {
+        let status = self.send(self.client.head(url), url).await?;
+
+        // Plenty of servers reject HEAD outright; retry those with GET
+        if matches!(status, 403 | 404 | 405 | 501) {
+            return self.send(self.client.get(url), url).await;
+        }
+        Ok(status)
+    }
+
+    /// Check a single URL, retrying timeouts, dropped connections, 429 and 5xx
+    /// with exponential backoff
+    pub async fn check(&self, url: &str) -> LinkStatus {
+        let mut attempt = 0;
+        loop {
+            let result = self.request(url).await;
+            let transient = match &result {
+                Ok(status) => *status == 429 || *status >= 500,
+                Err(error) => error.transient,
+            };
+
+            if transient && attempt < self.options.retries {
+                sleep(RETRY_BACKOFF * 2u32.pow(attempt)).await;
+                attempt += 1;
+                continue;
+            }
+
+            let checked_at = chrono::Utc::now().timestamp();
+            return match result {
+                Ok(status) => LinkStatus {
+                    ok: status < 400,
+                    status: Some(status),
+                    error: None,
+                    checked_at,
+                },
+                Err(error) => LinkStatus {
+                    ok: false,
+                    status: None,
+                    error: Some(error.message),
+                    checked_at,
+                },
+            };
+        }
+    }
+
+    /// Check every URL concurrently (bounded by `options.concurrency`)
+    pub async fn check_all(self: Arc<Self>, urls: Vec<String>) -> Vec<(String, LinkStatus)> {
+        let mut tasks = JoinSet::new();
+        for url in urls {
+            let checker = Arc::clone(&self);
+            tasks.spawn(async move {
+                let status = checker.check(&url).await;
+                (url, status)
+            });
+        }
+
+        let mut results = Vec::new();
+        while let Some(joined) = tasks.join_next().await {
+            match joined {
+                Ok(result) => results.push(result),
+                Err(e) => log::error!("Link check task failed: {}", e),
+            }
+        }
+        results
+    }
+}
+
+fn cache_path(repo_path: &str) -> PathBuf {
+    manager_dir(repo_path).join("link-cache.json")
+}
+
+/// Previously checked URLs; a missing or unreadable cache is simply empty
+fn load_cache(repo_path: &str) -> BTreeMap<String, LinkStatus> {
+    fs::read_to_string(cache_path(repo_path))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_cache(repo_path: &str, cache: &BTreeMap<String, LinkStatus>) -> Result<(), AppError> {
+    ensure_manager_dir(repo_path)?;
+    let path = cache_path(repo_path);
+
+    let content = serde_json::to_string_pretty(cache)
+        .map_err(|e| AppError::Other {
+            message: format!("Failed to serialize link cache: {}", e),
+        })?;
+    fs::write(&path, content).map_err(|e| AppError::io("write link cache", &path, e))
+}
+
+fn is_http(url: &str) -> bool {
+    let lower = url.to_lowercase();
+    lower.starts_with("http://") || lower.starts_with("https://")
+}
+
+/// Every http(s) URL in post bodies and `references.bib`, with where it was found
+fn collect_urls(repo_path: &str) -> Result<BTreeMap<String, Vec<LinkSource>>, AppError> {
+    let mut urls: BTreeMap<String, Vec<LinkSource>> = BTreeMap::new();
+
+    for post in load_posts(repo_path)? {
+        let lines = LineIndex::new(&post.content);
+        for target in link_targets(&post.content) {
+            if is_http(&target.url) {
+                let (line, _) = lines.position(target.offset);
+                urls.entry(target.url).or_default().push(LinkSource::Post {
+                    slug: post.slug.clone(),
+                    line,
+                });
+            }
+        }
+    }
+
+    // references.bib is optional
+    for entry in read_bib_file(repo_path.to_string()).unwrap_or_default() {
+        if let Some(url) = entry.url.filter(|u| is_http(u)) {
+            urls.entry(url).or_default().push(LinkSource::Reference { key: entry.key });
+        }
+    }
+
+    Ok(urls)
+}
+
+/// Check every external link in posts and references.
+///
+/// Results are cached in `.blog-manager/link-cache.json` and reused until
+/// `external_links.cache_ttl_hours` has passed; pass `force` to re-check all.
+#[tauri::command]
+pub async fn check_external_links(
+    repo_path: String,
+    force: Option<bool>,
+) -> Result<ExternalLinkReport, AppError> {
+    let config = load_config(&repo_path)?.external_links;
+    let urls = collect_urls(&repo_path)?;
+
+    let mut cache = if force.unwrap_or(false) {
+        BTreeMap::new()
+    } else {
+        load_cache(&repo_path)
+    };
+
+    let now = chrono::Utc::now().timestamp();
+    let ttl = (config.cache_ttl_hours * 3600) as i64;
+    let stale: Vec<String> = urls
+        .keys()
+        .filter(|url| cache.get(*url).map_or(true, |s| now - s.checked_at > ttl))
+        .cloned()
+        .collect();
+    let fresh: HashSet<String> = stale.iter().cloned().collect();
+
+    let client = reqwest::Client::builder()
+        .user_agent(config.user_agent.clone())
+        .timeout(Duration::from_secs(config.timeout_secs))
+        .redirect(reqwest::redirect::Policy::limited(10))
+        .build()
+        .map_err(|e| AppError::Other {
+            message: format!("Failed to create HTTP client: {}", e),
+        })?;
+
+    let checker = Arc::new(ExternalLinkChecker::new(client, CheckerOptions::from(&config)));
+    cache.extend(checker.check_all(stale).await);
+    cache.retain(|url, _| urls.contains_key(url));
+    save_cache(&repo_path, &cache)?;
+
+    let mut results: Vec<ExternalLinkResult> = urls
+        .into_iter()
+        .filter_map(|(url, sources)| {
+            let status = cache.get(&url)?.clone();
+            Some(ExternalLinkResult {
+                cached: !fresh.contains(&url),
+                url,
+                status,
+                sources,
+            })
+        })
+        .collect();
+    results.sort_by(|a, b| a.status.ok.cmp(&b.status.ok).then_with(|| a.url.cmp(&b.url)));
+
+    Ok(ExternalLinkReport {
+        links_checked: results.len(),
+        broken: results.iter().filter(|r| !r.status.ok).count(),
+        results,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::Mutex as StdMutex;
+    use std::thread;
+
+    /// A local HTTP server; returns its address and the paths it was asked for
+    fn serve() -> (String, Arc<StdMutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(StdMutex::new(Vec::new()));
+
+        let log = Arc::clone(&requests);
+        thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let log = Arc::clone(&log);
+                thread::spawn(move || {
+                    let mut buffer = [0; 4096];
+                    let read = stream.read(&mut buffer).unwrap_or(0);
+                    let request = String::from_utf8_lossy(&buffer[..read]).to_string();
+                    let path = request.split(' ').nth(1).unwrap_or("").to_string();
+                    let seen = {
+                        let mut log = log.lock().unwrap();
+                        log.push(path.clone());
+                        log.iter().filter(|p| **p == path).count()
+                    };
+
+                    let status = match path.as_str() {
+                        "/ok" => "200 OK",
+                        "/moved" => "301 Moved Permanently\r\nLocation: /ok",
+                        "/flaky" if seen == 1 => "429 Too Many Requests",
+                        "/flaky" => "200 OK",
+                        "/slow" => {
+                            thread::sleep(Duration::from_secs(1));
+                            "200 OK"
+                        }
+                        _ => "404 Not Found",
+                    };
+                    let _ = write!(stream, "HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n", status);
+                });
+            }
+        });
+        (address, requests)
+    }
+
+    #[test]
+    fn only_transient_failures_are_retried() {
+        let (address, requests) = serve();
+        let client = reqwest::Client::builder().timeout(Duration::from_millis(200)).build().unwrap();
+        let checker = Arc::new(ExternalLinkChecker::new(
+            client,
+            CheckerOptions {
+                concurrency: 4,
+                retries: 1,
+                per_host_delay: Duration::ZERO,
+            },
+        ));
+
+        let urls = ["/moved", "/missing", "/flaky", "/slow"].map(|path| format!("{}{}", address, path));
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+        let results = runtime.block_on(async {
+            let mut results = checker.clone().check_all(urls.to_vec()).await;
+            results.push(("not a url".to_string(), checker.check("not a url").await));
+            results
+        });
+
+        let status = |path: &str| {
+            let url = format!("{}{}", address, path);
+            results.iter().find(|(u, _)| *u == url).map(|(_, s)| s.clone()).unwrap()
+        };
+        assert_eq!(status("/moved").status, Some(200));
+        assert_eq!(status("/missing").status, Some(404));
+        assert!(!status("/missing").ok);
+        assert_eq!(status("/flaky").status, Some(200));
+        assert!(status("/slow").error.is_some());
+        assert!(results.iter().any(|(u, s)| u == "not a url" && s.error.is_some()));
+
+        let requests = requests.lock().unwrap();
+        let count = |path: &str| requests.iter().filter(|p| *p == path).count();
+        // A 404 to HEAD is retried once with GET, but never again
+        assert_eq!(count("/missing"), 2);
+        assert_eq!(count("/flaky"), 2);
+        assert_eq!(count("/slow"), 2);
+    }
+}
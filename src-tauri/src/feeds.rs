@@ -0,0 +1,149 @@
+use crate::blog::{load_posts, PostContent};
+use crate::config::{load_config, BlogConfig};
+use crate::error::AppError;
+use crate::seo::{canonical_url, escape_xml};
+use crate::transaction::Transaction;
+use chrono::{NaiveDate, NaiveTime};
+use std::path::Path;
+
+/// Newest posts included in each feed
+const MAX_ITEMS: usize = 20;
+
+const RSS_FILE: &str = "rss.xml";
+const ATOM_FILE: &str = "atom.xml";
+
+/// Midnight UTC of a `YYYY-MM-DD` post date
+fn post_time(date: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .ok()
+        .map(|d| d.and_time(NaiveTime::MIN).and_utc())
+}
+
+/// Posts with a valid date, newest first
+fn feed_posts(mut posts: Vec<PostContent>) -> Vec<PostContent> {
+    posts.retain(|p| post_time(&p.date).is_some());
+    posts.sort_by(|a, b| b.date.cmp(&a.date).then_with(|| a.slug.cmp(&b.slug)));
+    posts.truncate(MAX_ITEMS);
+    posts
+}
+
+fn last_updated(posts: &[PostContent]) -> chrono::DateTime<chrono::Utc> {
+    posts
+        .iter()
+        .filter_map(|p| post_time(if p.updated.is_empty() { &p.date } else { &p.updated }))
+        .max()
+        .unwrap_or_else(chrono::Utc::now)
+}
+
+fn rss_feed(posts: &[PostContent], config: &BlogConfig, site_url: &str) -> String {
+    let mut items = String::new();
+    for post in posts {
+        let link = canonical_url(config, &post.slug).unwrap_or_default();
+        items.push_str("    <item>\n");
+        items.push_str(&format!("      <title>{}</title>\n", escape_xml(&post.title)));
+        items.push_str(&format!("      <link>{}</link>\n", escape_xml(&link)));
+        items.push_str(&format!("      <guid isPermaLink=\"true\">{}</guid>\n", escape_xml(&link)));
+        if let Some(time) = post_time(&post.date) {
+            items.push_str(&format!("      <pubDate>{}</pubDate>\n", time.to_rfc2822()));
+        }
+        items.push_str(&format!("      <description>{}</description>\n", escape_xml(&post.excerpt)));
+        for tag in &post.tags {
+            items.push_str(&format!("      <category>{}</category>\n", escape_xml(tag)));
+        }
+        items.push_str("    </item>\n");
+    }
+
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:atom="http://www.w3.org/2005/Atom">
+  <channel>
+    <title>{title}</title>
+    <link>{site}/blog</link>
+    <description>{title}</description>
+    <atom:link href="{site}/{file}" rel="self" type="application/rss+xml" />
+    <lastBuildDate>{updated}</lastBuildDate>
+{items}  </channel>
+</rss>
+"#,
+        title = escape_xml(&config.site_name),
+        site = escape_xml(site_url),
+        file = RSS_FILE,
+        updated = last_updated(posts).to_rfc2822(),
+        items = items,
+    )
+}
+
+fn atom_feed(posts: &[PostContent], config: &BlogConfig, site_url: &str) -> String {
+    let mut entries = String::new();
+    for post in posts {
+        let link = escape_xml(&canonical_url(config, &post.slug).unwrap_or_default());
+        let published = post_time(&post.date).map(|t| t.to_rfc3339()).unwrap_or_default();
+        let updated = post_time(&post.updated)
+            .map(|t| t.to_rfc3339())
+            .unwrap_or_else(|| published.clone());
+
+        entries.push_str("  <entry>\n");
+        entries.push_str(&format!("    <title>{}</title>\n", escape_xml(&post.title)));
+        entries.push_str(&format!("    <link href=\"{}\" />\n", link));
+        entries.push_str(&format!("    <id>{}</id>\n", link));
+        entries.push_str(&format!("    <published>{}</published>\n", published));
+        entries.push_str(&format!("    <updated>{}</updated>\n", updated));
+        entries.push_str(&format!("    <summary>{}</summary>\n", escape_xml(&post.excerpt)));
+        for tag in &post.tags {
+            entries.push_str(&format!("    <category term=\"{}\" />\n", escape_xml(tag)));
+        }
+        entries.push_str("  </entry>\n");
+    }
+
+    // Atom requires an author; fall back to the site name
+    let author = if config.author.trim().is_empty() {
+        &config.site_name
+    } else {
+        &config.author
+    };
+
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>{title}</title>
+  <link href="{site}/blog" />
+  <link href="{site}/{file}" rel="self" />
+  <id>{site}/</id>
+  <updated>{updated}</updated>
+  <author>
+    <name>{author}</name>
+  </author>
+{entries}</feed>
+"#,
+        title = escape_xml(&config.site_name),
+        site = escape_xml(site_url),
+        file = ATOM_FILE,
+        updated = last_updated(posts).to_rfc3339(),
+        author = escape_xml(author),
+        entries = entries,
+    )
+}
+
+/// Write `static/rss.xml` and `static/atom.xml` for the newest posts. Returns
+/// the paths written, relative to the repo.
+#[tauri::command]
+pub fn generate_feeds(repo_path: String) -> Result<Vec<String>, AppError> {
+    let config = load_config(&repo_path)?;
+    let site_url = config.site_url.trim().trim_end_matches('/').to_string();
+    if site_url.is_empty() {
+        return Err(AppError::invalid("Set the site URL in the settings to generate feeds"));
+    }
+
+    let posts = feed_posts(load_posts(&repo_path)?);
+    let static_dir = Path::new(&repo_path).join("static");
+
+    let mut transaction = Transaction::default();
+    transaction.write(static_dir.join(RSS_FILE), rss_feed(&posts, &config, &site_url));
+    transaction.write(static_dir.join(ATOM_FILE), atom_feed(&posts, &config, &site_url));
+    transaction.commit()?;
+
+    Ok([RSS_FILE, ATOM_FILE]
+        .iter()
+        .map(|file| format!("static/{}", file))
+        .collect())
+}
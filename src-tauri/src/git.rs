@@ -0,0 +1,572 @@
+use crate::blog::{blog_dir, load_posts, read_post};
+use crate::config::manager_dir;
+use crate::error::AppError;
+use git2::build::CheckoutBuilder;
+use git2::{
+    BranchType, DiffOptions, ErrorCode, IndexAddOption, Oid, RebaseOptions, Repository, Signature, Status,
+    StatusOptions,
+};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Paths (relative to the repo root) the manager writes to
+const MANAGED_PATHS: &[&str] = &[
+    "src/routes/blog",
+    "static/blog",
+    "src/lib/related.ts",
+    "src/lib/references.bib",
+    "src/lib/data/references.ts",
+];
+const LISTING_PATH: &str = "src/routes/blog/+page.svelte";
+/// Rewritten along with any post whose related posts change
+const RELATED_PATH: &str = "src/lib/related.ts";
+const REFERENCE_PATHS: &[&str] = &["src/lib/references.bib", "src/lib/data/references.ts"];
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitFileStatus {
+    pub path: String,
+    /// `new`, `modified`, `deleted`, `renamed`, `typechange` or `conflicted`
+    pub status: String,
+    /// Whether the change is staged in the index
+    pub staged: bool,
+}
+
+/// How `finish_post` brings a post branch into the main branch
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum FinishStrategy {
+    Merge,
+    Rebase,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CommitInfo {
+    pub id: String,
+    pub short_id: String,
+    pub summary: String,
+    pub author: String,
+    /// RFC 3339 commit time
+    pub time: String,
+}
+
+/// Map a git2 error to `AppError::Git`, prefixed with what was being done
+fn git_error(action: &str) -> impl FnOnce(git2::Error) -> AppError + '_ {
+    move |e| AppError::git(format!("{}: {}", action, e.message()))
+}
+
+pub(crate) fn open_repo(repo_path: &str) -> Result<Repository, AppError> {
+    Repository::open(repo_path).map_err(git_error("Not a git repository"))
+}
+
+/// Files belonging to a post: its route directory and static assets
+fn post_paths(slug: &str) -> Vec<String> {
+    vec![format!("src/routes/blog/{}", slug), format!("static/blog/{}", slug)]
+}
+
+/// `path` relative to the repo root, with `/` separators as git expects
+fn repo_relative(repo_path: &str, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(repo_path).ok()?;
+    Some(
+        relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/"),
+    )
+}
+
+fn status_label(status: Status) -> Option<&'static str> {
+    if status.is_conflicted() {
+        Some("conflicted")
+    } else if status.intersects(Status::INDEX_NEW | Status::WT_NEW) {
+        Some("new")
+    } else if status.intersects(Status::INDEX_DELETED | Status::WT_DELETED) {
+        Some("deleted")
+    } else if status.intersects(Status::INDEX_RENAMED | Status::WT_RENAMED) {
+        Some("renamed")
+    } else if status.intersects(Status::INDEX_TYPECHANGE | Status::WT_TYPECHANGE) {
+        Some("typechange")
+    } else if status.intersects(Status::INDEX_MODIFIED | Status::WT_MODIFIED) {
+        Some("modified")
+    } else {
+        None
+    }
+}
+
+/// Fully staged: changed in the index with nothing further in the work tree
+fn is_staged(status: Status) -> bool {
+    let index = Status::INDEX_NEW
+        | Status::INDEX_MODIFIED
+        | Status::INDEX_DELETED
+        | Status::INDEX_RENAMED
+        | Status::INDEX_TYPECHANGE;
+    status.intersects(index) && !status.intersects(!index)
+}
+
+fn signature(repo: &Repository) -> Result<Signature<'static>, AppError> {
+    // Fall back to a fixed identity if user.name/user.email are not configured
+    repo.signature()
+        .or_else(|_| Signature::now("Blog Manager", "blog-manager@localhost"))
+        .map_err(git_error("Failed to create commit signature"))
+}
+
+/// Stage everything under `paths` (including deletions) and commit it on the
+/// current branch. Fails if none of the paths changed.
+pub(crate) fn commit_paths(repo: &Repository, paths: &[String], message: &str) -> Result<Oid, AppError> {
+    let mut index = repo.index().map_err(git_error("Failed to read index"))?;
+    index
+        .add_all(paths, IndexAddOption::DEFAULT, None)
+        .and_then(|_| index.update_all(paths, None))
+        .and_then(|_| index.write())
+        .map_err(git_error("Failed to stage files"))?;
+
+    let tree_id = index.write_tree().map_err(git_error("Failed to write tree"))?;
+    let tree = repo.find_tree(tree_id).map_err(git_error("Failed to read tree"))?;
+
+    // An unborn branch (fresh repo) has no parent commit
+    let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+    if parent.as_ref().is_some_and(|p| p.tree_id() == tree_id) {
+        return Err(AppError::git("Nothing to commit"));
+    }
+
+    let signature = signature(repo)?;
+    let parents: Vec<&git2::Commit> = parent.iter().collect();
+    repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &parents)
+        .map_err(git_error("Failed to commit"))
+}
+
+pub(crate) fn post_branch(slug: &str) -> String {
+    format!("post/{}", slug)
+}
+
+/// Check that `post/<slug>` can be created, before any file is written. The
+/// work tree must be clean, so the listing and `related.ts` committed on the
+/// branch hold this post's changes and nothing else.
+pub(crate) fn check_post_branch(repo_path: &str, slug: &str) -> Result<(), AppError> {
+    let repo = open_repo(repo_path)?;
+    repo.head()
+        .and_then(|head| head.peel_to_commit())
+        .map_err(|_| AppError::git("The website repo has no commits to branch from"))?;
+    if is_dirty(&repo)? {
+        return Err(AppError::git("The working tree has uncommitted changes; commit or discard them first"));
+    }
+
+    let name = post_branch(slug);
+    if repo.find_branch(&name, BranchType::Local).is_ok() {
+        return Err(AppError::git(format!("Branch {} already exists", name)));
+    }
+    Ok(())
+}
+
+/// Create `post/<slug>` at the current commit, switch to it and commit the
+/// post's files there (see `commit_post_files`). Only runs on a tree that
+/// was clean before the post was written (see `check_post_branch`). If
+/// anything fails, HEAD is put back where it was and the branch removed.
+pub(crate) fn commit_post_on_branch(repo_path: &str, slug: &str, written: &[PathBuf]) -> Result<(), AppError> {
+    let repo = open_repo(repo_path)?;
+    let head = repo.head().map_err(|_| AppError::git("The website repo has no commits to branch from"))?;
+    let commit = head
+        .peel_to_commit()
+        .map_err(|_| AppError::git("The website repo has no commits to branch from"))?;
+    let previous = head.is_branch().then(|| head.name().map(str::to_string)).flatten();
+
+    let name = post_branch(slug);
+    let mut branch = repo
+        .branch(&name, &commit, false)
+        .map_err(|e| AppError::git(format!("Failed to create branch {}: {}", name, e.message())))?;
+    let reference = branch.get().name().unwrap_or_default().to_string();
+
+    let result = repo
+        .set_head(&reference)
+        .map_err(|e| AppError::git(format!("Failed to switch to branch {}: {}", name, e.message())))
+        .and_then(|_| commit_post_files(repo_path, slug, written, None));
+    if result.is_err() {
+        let restored = match &previous {
+            Some(previous) => repo.set_head(previous),
+            None => repo.set_head_detached(commit.id()),
+        };
+        if let Err(e) = restored.and_then(|_| branch.delete()) {
+            log::error!("Failed to undo branch {}: {}", name, e.message());
+        }
+    }
+    result.map(|_| ())
+}
+
+/// Modified, deleted, conflicted or untracked files anywhere in the repo,
+/// apart from the manager's own directory, which every save writes to
+fn is_dirty(repo: &Repository) -> Result<bool, AppError> {
+    let mut options = StatusOptions::new();
+    options.include_untracked(true).include_ignored(false);
+    let statuses = repo
+        .statuses(Some(&mut options))
+        .map_err(git_error("Failed to read git status"))?;
+    let workdir = repo.workdir().unwrap_or(Path::new(""));
+    let manager = manager_dir(&workdir.to_string_lossy());
+    Ok(statuses
+        .iter()
+        .any(|entry| !entry.path().is_some_and(|path| workdir.join(path).starts_with(&manager))))
+}
+
+/// Local main branch: `main` if it exists, otherwise `master`
+fn main_branch(repo: &Repository) -> Result<String, AppError> {
+    ["main", "master"]
+        .iter()
+        .find(|name| repo.find_branch(name, BranchType::Local).is_ok())
+        .map(|name| name.to_string())
+        .ok_or_else(|| AppError::git("No main or master branch found"))
+}
+
+/// Replay the commits of `branch` onto `onto`, returning the new tip.
+/// Runs in memory, so a conflict leaves the repo untouched.
+fn rebase_onto(repo: &Repository, branch: &git2::Reference, onto: &git2::Reference) -> Result<Oid, AppError> {
+    let to_annotated = |r: &git2::Reference| {
+        repo.reference_to_annotated_commit(r)
+            .map_err(git_error("Failed to read branch"))
+    };
+    let mut options = RebaseOptions::new();
+    options.inmemory(true);
+
+    let mut rebase = repo
+        .rebase(Some(&to_annotated(branch)?), Some(&to_annotated(onto)?), None, Some(&mut options))
+        .map_err(git_error("Failed to start rebase"))?;
+    let signature = signature(repo)?;
+    let mut tip = onto.peel_to_commit().map_err(git_error("Failed to read branch"))?.id();
+
+    while let Some(operation) = rebase.next() {
+        let conflicted = operation.is_err()
+            || rebase.inmemory_index().map_or(true, |index| index.has_conflicts());
+        if conflicted {
+            let _ = rebase.abort();
+            return Err(AppError::git("Rebase stopped on a conflict; resolve it in git and try again"));
+        }
+
+        match rebase.commit(None, &signature, None) {
+            Ok(oid) => tip = oid,
+            // The change is already on the target branch
+            Err(e) if e.code() == ErrorCode::Applied => {}
+            Err(e) => {
+                let _ = rebase.abort();
+                return Err(AppError::git(format!("Failed to rebase: {}", e.message())));
+            }
+        }
+    }
+
+    rebase.finish(None).map_err(git_error("Failed to finish rebase"))?;
+    Ok(tip)
+}
+
+/// Merge `branch` into `target`: fast-forward when possible, otherwise a
+/// merge commit. Returns the new tip of `target`.
+fn merge_into(repo: &Repository, branch: &git2::Reference, target: &git2::Reference, message: &str) -> Result<Oid, AppError> {
+    let theirs = branch.peel_to_commit().map_err(git_error("Failed to read branch"))?;
+    let ours = target.peel_to_commit().map_err(git_error("Failed to read branch"))?;
+
+    let base = repo.merge_base(ours.id(), theirs.id()).ok();
+    if base == Some(theirs.id()) {
+        return Ok(ours.id());
+    }
+    if base == Some(ours.id()) {
+        return Ok(theirs.id());
+    }
+
+    let mut index = repo
+        .merge_commits(&ours, &theirs, None)
+        .map_err(git_error("Failed to merge"))?;
+    if index.has_conflicts() {
+        return Err(AppError::git("Merge has conflicts; resolve them in git and try again"));
+    }
+
+    let tree_id = index
+        .write_tree_to(repo)
+        .map_err(git_error("Failed to write tree"))?;
+    let tree = repo.find_tree(tree_id).map_err(git_error("Failed to read tree"))?;
+    let signature = signature(repo)?;
+    repo.commit(None, &signature, &signature, message, &tree, &[&ours, &theirs])
+        .map_err(git_error("Failed to commit merge"))
+}
+
+/// Whether `path` exists in the tree of the current HEAD commit
+fn in_head(repo: &Repository, path: &str) -> bool {
+    repo.head()
+        .and_then(|head| head.peel_to_tree())
+        .and_then(|tree| tree.get_path(Path::new(path)))
+        .is_ok()
+}
+
+/// Working-tree and index status of blog posts, post assets and references
+#[tauri::command]
+pub fn git_status(repo_path: String) -> Result<Vec<GitFileStatus>, AppError> {
+    let repo = open_repo(&repo_path)?;
+    let mut options = StatusOptions::new();
+    options.include_untracked(true).recurse_untracked_dirs(true);
+    for path in MANAGED_PATHS {
+        options.pathspec(path);
+    }
+
+    let statuses = repo
+        .statuses(Some(&mut options))
+        .map_err(git_error("Failed to read git status"))?;
+
+    Ok(statuses
+        .iter()
+        .filter_map(|entry| {
+            Some(GitFileStatus {
+                path: entry.path()?.to_string(),
+                status: status_label(entry.status())?.to_string(),
+                staged: is_staged(entry.status()),
+            })
+        })
+        .collect())
+}
+
+/// Commit a post's files, the blog listing and `related.ts`, plus `extra`:
+/// other files saving the post wrote, such as the navigation of its series
+pub(crate) fn commit_post_files(
+    repo_path: &str,
+    slug: &str,
+    extra: &[PathBuf],
+    message: Option<String>,
+) -> Result<String, AppError> {
+    let repo = open_repo(repo_path)?;
+    let post = read_post(repo_path.to_string(), slug.to_string())?;
+
+    let message = message.filter(|m| !m.trim().is_empty()).unwrap_or_else(|| {
+        if in_head(&repo, &format!("src/routes/blog/{}/+page.md", slug)) {
+            format!("Update post: {}", post.title)
+        } else {
+            format!("Add post: {}", post.title)
+        }
+    });
+
+    let mut paths = post_paths(slug);
+    paths.push(LISTING_PATH.to_string());
+    paths.push(RELATED_PATH.to_string());
+    paths.extend(extra.iter().filter_map(|path| repo_relative(repo_path, path)));
+    paths.sort();
+    paths.dedup();
+
+    let oid = commit_paths(&repo, &paths, &message)?;
+    Ok(oid.to_string())
+}
+
+/// Stage and commit a post's files, the blog listing, `related.ts` and the
+/// other parts of the post's series, whose navigation follows it. Without a
+/// message, one is generated: "Add post: <title>" or "Update post: <title>".
+#[tauri::command]
+pub fn commit_post(repo_path: String, slug: String, message: Option<String>) -> Result<String, AppError> {
+    let post = read_post(repo_path.clone(), slug.clone())?;
+    let parts: Vec<PathBuf> = if post.series.is_empty() {
+        Vec::new()
+    } else {
+        load_posts(&repo_path)?
+            .into_iter()
+            .filter(|p| p.series == post.series)
+            .map(|p| blog_dir(&repo_path).join(p.slug).join("+page.md"))
+            .collect()
+    };
+
+    commit_post_files(&repo_path, &slug, &parts, message)
+}
+
+/// Stage and commit `references.bib` and the generated `references.ts`
+#[tauri::command]
+pub fn commit_references(repo_path: String, message: Option<String>) -> Result<String, AppError> {
+    let repo = open_repo(&repo_path)?;
+    let message = message
+        .filter(|m| !m.trim().is_empty())
+        .unwrap_or_else(|| "Sync references".to_string());
+
+    let paths: Vec<String> = REFERENCE_PATHS.iter().map(|p| p.to_string()).collect();
+    let oid = commit_paths(&repo, &paths, &message)?;
+    Ok(oid.to_string())
+}
+
+/// Commits on the current branch that touched a post's files, newest first
+#[tauri::command]
+pub fn post_history(repo_path: String, slug: String) -> Result<Vec<CommitInfo>, AppError> {
+    let repo = open_repo(&repo_path)?;
+    if repo.head().is_err() {
+        return Ok(Vec::new());
+    }
+
+    let mut walk = repo.revwalk().map_err(git_error("Failed to read history"))?;
+    walk.push_head().map_err(git_error("Failed to read history"))?;
+
+    let mut history = Vec::new();
+    for oid in walk {
+        let commit = oid
+            .and_then(|oid| repo.find_commit(oid))
+            .map_err(git_error("Failed to read commit"))?;
+
+        let tree = commit.tree().map_err(git_error("Failed to read commit"))?;
+        let parent_tree = commit.parent(0).ok().and_then(|p| p.tree().ok());
+
+        let mut options = DiffOptions::new();
+        for path in post_paths(&slug) {
+            options.pathspec(path);
+        }
+        let diff = repo
+            .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), Some(&mut options))
+            .map_err(git_error("Failed to diff commit"))?;
+        if diff.deltas().len() == 0 {
+            continue;
+        }
+
+        let time = chrono::DateTime::from_timestamp(commit.time().seconds(), 0)
+            .map(|t| t.to_rfc3339())
+            .unwrap_or_default();
+        let id = commit.id().to_string();
+        history.push(CommitInfo {
+            short_id: id[..7].to_string(),
+            id,
+            summary: commit.summary().unwrap_or_default().to_string(),
+            author: commit.author().name().unwrap_or_default().to_string(),
+            time,
+        });
+    }
+
+    Ok(history)
+}
+
+/// Bring a post's `post/<slug>` branch into the main branch (`main`, or
+/// `master`) by merging or rebasing, then switch to the main branch and
+/// delete the post branch. Refuses to run with uncommitted changes.
+#[tauri::command]
+pub fn finish_post(repo_path: String, slug: String, strategy: Option<FinishStrategy>) -> Result<String, AppError> {
+    let repo = open_repo(&repo_path)?;
+    if is_dirty(&repo)? {
+        return Err(AppError::git("The working tree has uncommitted changes; commit or discard them first"));
+    }
+
+    let name = post_branch(&slug);
+    let mut branch = repo
+        .find_branch(&name, BranchType::Local)
+        .map_err(|_| AppError::git(format!("Branch not found: {}", name)))?;
+    let main = main_branch(&repo)?;
+    let target = repo
+        .find_branch(&main, BranchType::Local)
+        .map_err(git_error("Failed to read branch"))?;
+
+    let strategy = strategy.unwrap_or(FinishStrategy::Merge);
+    let tip = match strategy {
+        FinishStrategy::Merge => merge_into(&repo, branch.get(), target.get(), &format!("Merge branch '{}'", name))?,
+        FinishStrategy::Rebase => rebase_onto(&repo, branch.get(), target.get())?,
+    };
+
+    let target_ref = target.get().name().unwrap_or_default().to_string();
+    repo.reference(&target_ref, tip, true, &format!("finish_post: {}", name))
+        .map_err(|e| AppError::git(format!("Failed to update {}: {}", main, e.message())))?;
+
+    // The tree is clean, so a forced checkout only applies the new commits
+    repo.set_head(&target_ref)
+        .and_then(|_| repo.checkout_head(Some(CheckoutBuilder::new().force())))
+        .map_err(|e| AppError::git(format!("Failed to switch to {}: {}", main, e.message())))?;
+
+    branch
+        .delete()
+        .map_err(|e| AppError::git(format!("Failed to delete branch {}: {}", name, e.message())))?;
+
+    Ok(match strategy {
+        FinishStrategy::Merge => format!("Merged {} into {}", name, main),
+        FinishStrategy::Rebase => format!("Rebased {} onto {}", name, main),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blog::tests::{create, fake_repo};
+    use crate::blog::{create_blog_post, update_post};
+    use crate::series::create_series;
+
+    /// A repo on `main` with two posts in a series, all committed
+    fn committed_series(name: &str) -> (String, Repository) {
+        let repo = fake_repo(name);
+        let git = Repository::init(&repo).unwrap();
+        git.set_head("refs/heads/main").unwrap();
+        create(&repo, "a", "Part A", "Ownership in Rust");
+        create(&repo, "b", "Part B", "Borrowing in Rust");
+        create_series(repo.clone(), "Intro".into(), vec!["a".into(), "b".into()]).unwrap();
+        commit_paths(&git, &[".".to_string()], "Initial commit").unwrap();
+        (repo, git)
+    }
+
+    #[test]
+    fn post_commits_take_everything_the_save_wrote() {
+        let (repo, git) = committed_series("git-commit-post");
+        let a = read_post(repo.clone(), "a".into()).unwrap();
+        update_post(repo.clone(), "a".into(), "Part One".into(), a.excerpt, a.content, a.tags, a.version).unwrap();
+
+        let changed: Vec<String> = git_status(repo.clone()).unwrap().into_iter().map(|s| s.path).collect();
+        assert!(changed.contains(&"src/lib/related.ts".to_string()));
+        assert!(changed.contains(&"src/routes/blog/b/+page.md".to_string()));
+
+        commit_post(repo.clone(), "a".into(), None).unwrap();
+        assert!(git_status(repo).unwrap().is_empty());
+        assert!(!is_dirty(&git).unwrap());
+    }
+
+    #[test]
+    fn branch_drafts_can_be_finished() {
+        let (repo, git) = committed_series("git-branch-draft");
+        create_blog_post(
+            repo.clone(),
+            "Part C".into(),
+            "c".into(),
+            "Excerpt".into(),
+            "Lifetimes in Rust".into(),
+            vec!["tag".into()],
+            "keywords".into(),
+            Some(true),
+        )
+        .unwrap();
+        assert_eq!(git.head().unwrap().shorthand(), Some("post/c"));
+        assert!(!is_dirty(&git).unwrap());
+
+        finish_post(repo.clone(), "c".into(), None).unwrap();
+        assert_eq!(git.head().unwrap().shorthand(), Some("main"));
+    }
+
+    #[test]
+    fn branch_problems_stop_the_post_before_it_is_written() {
+        let (repo, git) = committed_series("git-branch-exists");
+        let head = git.head().unwrap().peel_to_commit().unwrap();
+        git.branch("post/c", &head, false).unwrap();
+
+        let error = create_blog_post(
+            repo.clone(),
+            "Part C".into(),
+            "c".into(),
+            "Excerpt".into(),
+            "Body".into(),
+            vec![],
+            "keywords".into(),
+            Some(true),
+        )
+        .unwrap_err();
+        assert_eq!(error.code(), "git");
+        assert!(!blog_dir(&repo).join("c").exists());
+        assert_eq!(git.head().unwrap().shorthand(), Some("main"));
+    }
+
+    #[test]
+    fn branch_drafts_need_a_clean_tree() {
+        let (repo, git) = committed_series("git-branch-dirty");
+        std::fs::write(Path::new(&repo).join("notes.txt"), "todo").unwrap();
+
+        let error = create_blog_post(
+            repo.clone(),
+            "Part C".into(),
+            "c".into(),
+            "Excerpt".into(),
+            "Body".into(),
+            vec![],
+            "keywords".into(),
+            Some(true),
+        )
+        .unwrap_err();
+        assert_eq!(error.code(), "git");
+        assert!(!blog_dir(&repo).join("c").exists());
+        assert_eq!(git.head().unwrap().shorthand(), Some("main"));
+    }
+}
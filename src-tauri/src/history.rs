@@ -0,0 +1,236 @@
+use crate::assets::hash_bytes;
+use crate::blog::{blog_dir, parse_post, post_version, read_post, update_post, PostContent};
+use crate::config::{ensure_manager_dir, load_config, manager_dir, HistoryConfig};
+use crate::error::AppError;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+/// One saved version of a post's `+page.md`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Revision {
+    pub id: String,
+    /// RFC 3339 time the snapshot was taken
+    pub timestamp: String,
+    pub title: String,
+    pub word_count: usize,
+    /// Size of the uncompressed file in bytes
+    pub size: usize,
+    /// SHA-256 of the file, naming its compressed object
+    pub hash: String,
+}
+
+/// Per-post history: `index.json` lists revisions oldest first, and each
+/// distinct file content is stored once as `objects/<hash>.gz`
+fn history_dir(repo_path: &str, slug: &str) -> PathBuf {
+    manager_dir(repo_path).join("history").join(slug)
+}
+
+fn object_path(repo_path: &str, slug: &str, hash: &str) -> PathBuf {
+    history_dir(repo_path, slug).join("objects").join(format!("{}.gz", hash))
+}
+
+fn load_index(repo_path: &str, slug: &str) -> Vec<Revision> {
+    fs::read_to_string(history_dir(repo_path, slug).join("index.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_index(repo_path: &str, slug: &str, revisions: &[Revision]) -> Result<(), AppError> {
+    let path = history_dir(repo_path, slug).join("index.json");
+    let content = serde_json::to_string_pretty(revisions).map_err(|e| AppError::Other {
+        message: format!("Failed to serialize history: {}", e),
+    })?;
+    fs::write(&path, content).map_err(|e| AppError::io("write history", &path, e))
+}
+
+fn write_object(path: &Path, content: &str) -> Result<(), AppError> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(content.as_bytes())
+        .and_then(|_| encoder.finish())
+        .and_then(|compressed| fs::write(path, compressed))
+        .map_err(|e| AppError::io("write revision", path, e))
+}
+
+fn read_object(path: &Path) -> Result<String, AppError> {
+    let compressed = fs::read(path).map_err(|e| AppError::io("read revision", path, e))?;
+    let mut content = String::new();
+    GzDecoder::new(compressed.as_slice())
+        .read_to_string(&mut content)
+        .map_err(|e| AppError::io("decompress revision", path, e))?;
+    Ok(content)
+}
+
+/// Drop revisions beyond the configured count or age, always keeping the newest
+fn prune(revisions: &mut Vec<Revision>, config: &HistoryConfig) {
+    if config.max_age_days > 0 {
+        let cutoff = chrono::Utc::now() - chrono::Duration::days(config.max_age_days as i64);
+        let newest = revisions.last().map(|r| r.id.clone());
+        revisions.retain(|r| {
+            Some(&r.id) == newest.as_ref()
+                || chrono::DateTime::parse_from_rfc3339(&r.timestamp).map_or(true, |t| t >= cutoff)
+        });
+    }
+
+    if config.max_revisions > 0 && revisions.len() > config.max_revisions {
+        revisions.drain(..revisions.len() - config.max_revisions);
+    }
+}
+
+/// Delete stored objects no revision refers to any more
+fn collect_garbage(repo_path: &str, slug: &str, revisions: &[Revision]) {
+    let live: HashSet<String> = revisions.iter().map(|r| format!("{}.gz", r.hash)).collect();
+    let Ok(entries) = fs::read_dir(history_dir(repo_path, slug).join("objects")) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        if !live.contains(&*entry.file_name().to_string_lossy()) {
+            if let Err(e) = fs::remove_file(entry.path()) {
+                log::warn!("Failed to remove revision object: {}", e);
+            }
+        }
+    }
+}
+
+/// Snapshot `content` as the newest revision of a post, unless it is identical
+/// to the current newest one
+pub(crate) fn record_revision(repo_path: &str, slug: &str, content: &str) -> Result<(), AppError> {
+    let hash = hash_bytes(content.as_bytes());
+    let mut revisions = load_index(repo_path, slug);
+    if revisions.last().is_some_and(|r| r.hash == hash) {
+        return Ok(());
+    }
+
+    ensure_manager_dir(repo_path)?;
+    let objects = history_dir(repo_path, slug).join("objects");
+    fs::create_dir_all(&objects)
+        .map_err(|e| AppError::io("create history directory", &objects, e))?;
+
+    let object = object_path(repo_path, slug, &hash);
+    if !object.exists() {
+        write_object(&object, content)?;
+    }
+
+    let now = chrono::Utc::now();
+    let base_id = now.format("%Y%m%dT%H%M%S%3fZ").to_string();
+    let id = (1..)
+        .map(|n| if n == 1 { base_id.clone() } else { format!("{}-{}", base_id, n) })
+        .find(|id| !revisions.iter().any(|r| &r.id == id))
+        .unwrap();
+
+    let post = parse_post(slug.to_string(), content);
+    revisions.push(Revision {
+        id,
+        timestamp: now.to_rfc3339(),
+        title: post.title,
+        word_count: post.word_count,
+        size: content.len(),
+        hash,
+    });
+
+    prune(&mut revisions, &load_config(repo_path)?.history);
+    save_index(repo_path, slug, &revisions)?;
+    collect_garbage(repo_path, slug, &revisions);
+    Ok(())
+}
+
+/// Snapshot the post file as it currently is on disk, if it exists. History
+/// is best-effort: a failed snapshot is logged and never stops a save.
+pub(crate) fn record_current(repo_path: &str, slug: &str) {
+    let post_file = blog_dir(repo_path).join(slug).join("+page.md");
+    if let Ok(content) = fs::read_to_string(&post_file) {
+        if let Err(e) = record_revision(repo_path, slug, &content) {
+            log::warn!("Failed to record revision for {}: {}", slug, e);
+        }
+    }
+}
+
+/// The newest stored post file whose `post_version` is `version`, if any
+/// revision still has it
+pub(crate) fn content_by_version(repo_path: &str, slug: &str, version: &str) -> Option<String> {
+    load_index(repo_path, slug)
+        .iter()
+        .rev()
+        .filter_map(|r| read_object(&object_path(repo_path, slug, &r.hash)).ok())
+        .find(|content| post_version(content) == version)
+}
+
+fn revision_content(repo_path: &str, slug: &str, id: &str) -> Result<String, AppError> {
+    let revision = load_index(repo_path, slug)
+        .into_iter()
+        .find(|r| r.id == id)
+        .ok_or_else(|| AppError::invalid(format!("Revision not found: {}", id)))?;
+    read_object(&object_path(repo_path, slug, &revision.hash))
+}
+
+/// List a post's saved revisions, newest first
+#[tauri::command]
+pub fn list_revisions(repo_path: String, slug: String) -> Result<Vec<Revision>, AppError> {
+    let mut revisions = load_index(&repo_path, &slug);
+    revisions.reverse();
+    Ok(revisions)
+}
+
+/// Read a post as it was at the given revision
+#[tauri::command]
+pub fn read_revision(repo_path: String, slug: String, id: String) -> Result<PostContent, AppError> {
+    let content = revision_content(&repo_path, &slug, &id)?;
+    Ok(parse_post(slug, &content))
+}
+
+/// Save a revision's title, excerpt, tags and body as the post's current
+/// version. The current version stays in the history, so this can be undone.
+#[tauri::command]
+pub fn restore_revision(repo_path: String, slug: String, id: String) -> Result<String, AppError> {
+    let revision = read_revision(repo_path.clone(), slug.clone(), id.clone())?;
+    let current = read_post(repo_path.clone(), slug.clone())?;
+    update_post(
+        repo_path,
+        slug.clone(),
+        revision.title,
+        revision.excerpt,
+        revision.content,
+        revision.tags,
+        current.version,
+    )?;
+    Ok(format!("Restored {} to revision {}", slug, id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blog::tests::{create, fake_repo};
+
+    #[test]
+    fn history_stays_out_of_git() {
+        let repo = fake_repo("history-gitignore");
+        create(&repo, "post", "Post", "Body");
+
+        let gitignore = fs::read_to_string(manager_dir(&repo).join(".gitignore")).unwrap();
+        assert!(gitignore.lines().any(|l| l == "*"));
+        assert!(gitignore.lines().any(|l| l == "!config.json"));
+    }
+
+    #[test]
+    fn failed_snapshots_do_not_stop_saves() {
+        let repo = fake_repo("history-best-effort");
+        create(&repo, "post", "Post", "Body");
+
+        // A file where the manager directory belongs makes every snapshot fail
+        fs::remove_dir_all(manager_dir(&repo)).unwrap();
+        fs::write(manager_dir(&repo), "").unwrap();
+
+        let post = read_post(repo.clone(), "post".into()).unwrap();
+        update_post(repo.clone(), "post".into(), post.title, post.excerpt, "New body".into(), post.tags, post.version)
+            .unwrap();
+        assert_eq!(read_post(repo, "post".into()).unwrap().content, "New body");
+    }
+}
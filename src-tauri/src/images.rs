@@ -0,0 +1,343 @@
+use crate::assets::{asset_dir, asset_entry, unique_file_name, ImportedAsset, PostAsset};
+use crate::config::{AssetLocation, ImageConfig, VariantFormat};
+use crate::error::AppError;
+use crate::transaction::Transaction;
+use image::codecs::avif::AvifEncoder;
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::PngEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::imageops::FilterType;
+use image::metadata::Orientation;
+use image::{DynamicImage, ImageDecoder, ImageReader};
+use std::io::Cursor;
+use std::path::Path;
+
+const RASTER_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "webp"];
+const JPEG_QUALITY: u8 = 90;
+
+impl VariantFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            VariantFormat::Webp => "webp",
+            VariantFormat::Avif => "avif",
+        }
+    }
+
+    fn mime_type(self) -> &'static str {
+        match self {
+            VariantFormat::Webp => "image/webp",
+            VariantFormat::Avif => "image/avif",
+        }
+    }
+}
+
+/// Images we can decode and re-encode (GIFs are left alone to keep animation)
+pub(crate) fn is_raster(name: &str) -> bool {
+    extension_of(name).is_some_and(|e| RASTER_EXTENSIONS.contains(&e.as_str()))
+}
+
+fn extension_of(name: &str) -> Option<String> {
+    Path::new(name)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+}
+
+/// 8-bit RGB(A), the pixel layouts every encoder here accepts
+fn to_8bit(img: &DynamicImage) -> DynamicImage {
+    if img.color().has_alpha() {
+        DynamicImage::ImageRgba8(img.to_rgba8())
+    } else {
+        DynamicImage::ImageRgb8(img.to_rgb8())
+    }
+}
+
+fn encode_variant(img: &DynamicImage, format: VariantFormat, config: &ImageConfig) -> Result<Vec<u8>, AppError> {
+    let img = to_8bit(img);
+    let mut out = Vec::new();
+
+    let result = match format {
+        VariantFormat::Webp => img.write_with_encoder(WebPEncoder::new_lossless(&mut out)),
+        VariantFormat::Avif => img.write_with_encoder(AvifEncoder::new_with_speed_quality(
+            &mut out,
+            config.avif_speed.clamp(1, 10),
+            config.avif_quality.clamp(1, 100),
+        )),
+    };
+
+    result.map_err(|e| AppError::Other {
+        message: format!("Failed to encode {} image: {}", format.extension(), e),
+    })?;
+    Ok(out)
+}
+
+/// Re-encode a decoded image in its original container format
+fn encode_original(img: &DynamicImage, extension: &str) -> Result<Vec<u8>, AppError> {
+    let img = to_8bit(img);
+    let mut out = Vec::new();
+
+    let result = match extension {
+        "jpg" | "jpeg" => img
+            .to_rgb8()
+            .write_with_encoder(JpegEncoder::new_with_quality(&mut out, JPEG_QUALITY)),
+        "png" => img.write_with_encoder(PngEncoder::new(&mut out)),
+        _ => img.write_with_encoder(WebPEncoder::new_lossless(&mut out)),
+    };
+
+    result.map_err(|e| AppError::Other {
+        message: format!("Failed to encode image: {}", e),
+    })?;
+    Ok(out)
+}
+
+/// Drop APP1 (Exif/XMP) and APP13 (IPTC) segments from a JPEG without
+/// touching the compressed image data
+fn strip_jpeg_metadata(bytes: &[u8]) -> Option<Vec<u8>> {
+    if !bytes.starts_with(&[0xFF, 0xD8]) {
+        return None;
+    }
+
+    let mut out = vec![0xFF, 0xD8];
+    let mut pos = 2;
+
+    while pos + 4 <= bytes.len() {
+        if bytes[pos] != 0xFF {
+            return None;
+        }
+        let marker = bytes[pos + 1];
+
+        // Start of scan: the rest is entropy-coded data
+        if marker == 0xDA {
+            out.extend_from_slice(&bytes[pos..]);
+            return Some(out);
+        }
+
+        let length = u16::from_be_bytes([bytes[pos + 2], bytes[pos + 3]]) as usize;
+        let end = pos + 2 + length;
+        if end > bytes.len() {
+            return None;
+        }
+        if marker != 0xE1 && marker != 0xED {
+            out.extend_from_slice(&bytes[pos..end]);
+        }
+        pos = end;
+    }
+
+    None
+}
+
+/// Drop metadata chunks (eXIf, text and timestamp) from a PNG
+fn strip_png_metadata(bytes: &[u8]) -> Option<Vec<u8>> {
+    const SIGNATURE: &[u8] = &[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+    const DROPPED: &[&[u8]] = &[b"eXIf", b"tEXt", b"iTXt", b"zTXt", b"tIME"];
+
+    if !bytes.starts_with(SIGNATURE) {
+        return None;
+    }
+
+    let mut out = SIGNATURE.to_vec();
+    let mut pos = SIGNATURE.len();
+
+    while pos + 8 <= bytes.len() {
+        let length = u32::from_be_bytes(bytes[pos..pos + 4].try_into().ok()?) as usize;
+        let chunk_type = &bytes[pos + 4..pos + 8];
+        let end = pos + 12 + length;
+        if end > bytes.len() {
+            return None;
+        }
+        if !DROPPED.contains(&chunk_type) {
+            out.extend_from_slice(&bytes[pos..end]);
+        }
+        pos = end;
+    }
+
+    Some(out)
+}
+
+/// The original file with privacy-sensitive metadata removed. JPEG and PNG
+/// are stripped losslessly; anything else, or an image whose EXIF rotation
+/// had to be applied, is re-encoded.
+fn stripped_original(bytes: &[u8], img: &DynamicImage, extension: &str, rotated: bool) -> Result<Vec<u8>, AppError> {
+    let stripped = match extension {
+        _ if rotated => None,
+        "jpg" | "jpeg" => strip_jpeg_metadata(bytes),
+        "png" => strip_png_metadata(bytes),
+        _ => None,
+    };
+
+    match stripped {
+        Some(stripped) => Ok(stripped),
+        None => encode_original(img, extension),
+    }
+}
+
+fn stage_asset(
+    transaction: &mut Transaction,
+    repo_path: &str,
+    slug: &str,
+    location: AssetLocation,
+    name: &str,
+    data: Vec<u8>,
+) -> Result<PostAsset, AppError> {
+    let dir = asset_dir(repo_path, slug, location);
+    let name = unique_file_name(&dir, name);
+    let path = dir.join(&name);
+
+    let asset = asset_entry(repo_path, slug, &path, location, data.len() as u64)
+        .ok_or_else(|| AppError::invalid(format!("Not a valid asset name: {}", name)))?;
+    transaction.write(path, data);
+    Ok(asset)
+}
+
+fn picture_snippet(
+    alt: &str,
+    sources: &[(VariantFormat, Vec<(u32, PostAsset)>)],
+    fallback: &PostAsset,
+    dimensions: (u32, u32),
+    sizes: &str,
+) -> String {
+    let mut snippet = String::from("<picture>\n");
+
+    for (format, variants) in sources {
+        let srcset = variants
+            .iter()
+            .map(|(width, asset)| format!("{} {}w", asset.url, width))
+            .collect::<Vec<_>>()
+            .join(", ");
+        snippet.push_str(&format!(
+            "    <source type=\"{}\" srcset=\"{}\" sizes=\"{}\" />\n",
+            format.mime_type(),
+            srcset,
+            sizes
+        ));
+    }
+
+    snippet.push_str(&format!(
+        "    <img src=\"{}\" alt=\"{}\" width=\"{}\" height=\"{}\" loading=\"lazy\" decoding=\"async\" />\n</picture>",
+        fallback.url, alt, dimensions.0, dimensions.1
+    ));
+    snippet
+}
+
+/// Import a raster image as resized WebP/AVIF variants (plus, optionally,
+/// the metadata-stripped original) and build a `<picture>` snippet for it.
+/// Variants that are not smaller than the imported file are left out.
+/// The files are staged in `transaction`; nothing is written until it commits.
+pub(crate) fn import_image(
+    transaction: &mut Transaction,
+    repo_path: &str,
+    slug: &str,
+    bytes: &[u8],
+    name: &str,
+    location: AssetLocation,
+    config: &ImageConfig,
+) -> Result<ImportedAsset, AppError> {
+    let mut decoder = ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(|e| AppError::invalid(format!("Failed to read image: {}", e)))?
+        .into_decoder()
+        .map_err(|e| AppError::invalid(format!("Failed to decode image: {}", e)))?;
+    let orientation = decoder.orientation().unwrap_or(Orientation::NoTransforms);
+    let mut img = DynamicImage::from_decoder(decoder)
+        .map_err(|e| AppError::invalid(format!("Failed to decode image: {}", e)))?;
+    img.apply_orientation(orientation);
+
+    let (width, height) = (img.width(), img.height());
+    let stem = Path::new(name).file_stem().unwrap_or_default().to_string_lossy().to_string();
+    let extension = extension_of(name).unwrap_or_default();
+
+    let mut widths: Vec<u32> = config.widths.iter().copied().filter(|w| *w <= width).collect();
+    if widths.is_empty() {
+        widths.push(width);
+    }
+    widths.sort_unstable();
+    widths.dedup();
+
+    // Browsers take the first <source> they support, so list AVIF first
+    let mut formats = config.formats.clone();
+    formats.sort_by_key(|f| *f != VariantFormat::Avif);
+    formats.dedup();
+
+    let mut sources: Vec<(VariantFormat, Vec<(u32, PostAsset)>)> =
+        formats.iter().map(|f| (*f, Vec::new())).collect();
+
+    for &target_width in &widths {
+        let resized = if target_width == width {
+            img.clone()
+        } else {
+            img.resize(target_width, u32::MAX, FilterType::Lanczos3)
+        };
+
+        for (format, variants) in sources.iter_mut() {
+            let data = encode_variant(&resized, *format, config)?;
+            // Lossless WebP in particular can outweigh a JPEG it was made from
+            if data.len() >= bytes.len() {
+                continue;
+            }
+            let file_name = format!("{}-{}.{}", stem, target_width, format.extension());
+            variants.push((target_width, stage_asset(transaction, repo_path, slug, location, &file_name, data)?));
+        }
+    }
+
+    sources.retain(|(_, variants)| !variants.is_empty());
+
+    let original = if config.keep_original || sources.is_empty() {
+        let rotated = orientation != Orientation::NoTransforms;
+        let data = stripped_original(bytes, &img, &extension, rotated)?;
+        Some(stage_asset(transaction, repo_path, slug, location, name, data)?)
+    } else {
+        None
+    };
+
+    let alt = stem.replace('-', " ");
+    let fallback = match &original {
+        Some(original) => original,
+        // Prefer the most widely supported format for the plain <img>
+        None => sources
+            .iter()
+            .rev()
+            .find_map(|(_, variants)| variants.last().map(|(_, asset)| asset))
+            .ok_or_else(|| AppError::Other {
+                message: "No image variants were generated".to_string(),
+            })?,
+    };
+    let snippet = picture_snippet(&alt, &sources, fallback, (width, height), &config.sizes);
+
+    Ok(ImportedAsset {
+        asset: fallback.clone(),
+        variants: sources.into_iter().flat_map(|(_, v)| v.into_iter().map(|(_, a)| a)).collect(),
+        snippet,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageFormat, RgbImage};
+
+    #[test]
+    fn variants_larger_than_the_source_are_skipped() {
+        // Noise compresses badly losslessly, so lossless WebP outweighs the JPEG
+        let img = RgbImage::from_fn(600, 400, |x, y| {
+            let n = x.wrapping_mul(2_654_435_761).wrapping_add(y.wrapping_mul(40_503)) >> 8;
+            image::Rgb([n as u8, (n >> 8) as u8, (n >> 16) as u8])
+        });
+        let mut jpeg = Vec::new();
+        img.write_to(&mut Cursor::new(&mut jpeg), ImageFormat::Jpeg).unwrap();
+
+        let config = ImageConfig {
+            widths: vec![600],
+            formats: vec![VariantFormat::Webp],
+            keep_original: false,
+            ..ImageConfig::default()
+        };
+        let repo = std::env::temp_dir().join("blog-manager-images-skip").to_string_lossy().to_string();
+        let _ = std::fs::remove_dir_all(&repo);
+        let mut transaction = Transaction::default();
+        let imported =
+            import_image(&mut transaction, &repo, "post", &jpeg, "noise.jpg", AssetLocation::Static, &config).unwrap();
+
+        assert!(imported.variants.is_empty());
+        assert_eq!(imported.asset.name, "noise.jpg");
+        assert!(!imported.snippet.contains("image/webp"));
+    }
+}
@@ -1,32 +1,110 @@
-mod blog;
-mod bibtex;
-
-use blog::{create_blog_post, get_existing_posts, read_post, update_post, slugify};
-use bibtex::{read_bib_file, sync_references};
-
-#[cfg_attr(mobile, tauri::mobile_entry_point)]
-pub fn run() {
-    tauri::Builder::default()
-        .plugin(tauri_plugin_store::Builder::new().build())
-        .setup(|app| {
-            if cfg!(debug_assertions) {
-                app.handle().plugin(
-                    tauri_plugin_log::Builder::default()
-                        .level(log::LevelFilter::Info)
-                        .build(),
-                )?;
-            }
-            Ok(())
-        })
-        .invoke_handler(tauri::generate_handler![
-            create_blog_post,
-            get_existing_posts,
-            read_post,
-            update_post,
-            slugify,
-            read_bib_file,
-            sync_references,
-        ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
-}
+mod assets;
+mod blog;
+mod bibtex;
+pub mod cli;
+mod config;
+mod diff;
+mod drafts;
+mod error;
+mod external_links;
+mod feeds;
+mod git;
+mod history;
+mod images;
+mod links;
+mod lint;
+mod markdown;
+mod related;
+mod search;
+mod seo;
+mod series;
+mod social_card;
+mod summary;
+mod svelte;
+mod transaction;
+mod watcher;
+
+use assets::{import_asset, list_post_assets};
+use blog::{
+    create_blog_post, delete_post, get_existing_posts, open_post_for_edit, read_post, render_preview, set_post_toc,
+    update_post, slugify,
+};
+use bibtex::{read_bib_file, sync_references};
+use config::{get_config, save_config};
+use diff::{diff_post, diff_post_buffer};
+use drafts::{autosave_draft, discard_draft, flush_drafts, recover_drafts, DraftStore};
+use external_links::check_external_links;
+use feeds::generate_feeds;
+use git::{commit_post, commit_references, finish_post, git_status, post_history};
+use history::{list_revisions, read_revision, restore_revision};
+use links::check_links;
+use lint::lint_post;
+use related::update_related_posts;
+use search::{search_posts, SearchIndex};
+use series::{create_series, list_series, reorder_series, set_post_series};
+use social_card::generate_social_card;
+use watcher::{watch_repo, RepoWatcher};
+
+#[cfg_attr(mobile, tauri::mobile_entry_point)]
+pub fn run() {
+    tauri::Builder::default()
+        .plugin(tauri_plugin_store::Builder::new().build())
+        .manage(SearchIndex::default())
+        .manage(DraftStore::default())
+        .manage(RepoWatcher::default())
+        .setup(|app| {
+            if cfg!(debug_assertions) {
+                app.handle().plugin(
+                    tauri_plugin_log::Builder::default()
+                        .level(log::LevelFilter::Info)
+                        .build(),
+                )?;
+            }
+            Ok(())
+        })
+        .invoke_handler(tauri::generate_handler![
+            create_blog_post,
+            get_existing_posts,
+            read_post,
+            open_post_for_edit,
+            update_post,
+            delete_post,
+            set_post_toc,
+            render_preview,
+            slugify,
+            read_bib_file,
+            sync_references,
+            search_posts,
+            get_config,
+            save_config,
+            lint_post,
+            check_links,
+            check_external_links,
+            generate_feeds,
+            update_related_posts,
+            import_asset,
+            list_post_assets,
+            generate_social_card,
+            list_series,
+            create_series,
+            reorder_series,
+            set_post_series,
+            list_revisions,
+            read_revision,
+            restore_revision,
+            diff_post,
+            diff_post_buffer,
+            git_status,
+            commit_post,
+            commit_references,
+            post_history,
+            finish_post,
+            autosave_draft,
+            discard_draft,
+            flush_drafts,
+            recover_drafts,
+            watch_repo,
+        ])
+        .run(tauri::generate_context!())
+        .expect("error while running tauri application");
+}
@@ -0,0 +1,257 @@
+use crate::blog::{blog_dir, load_posts, PostContent};
+use crate::error::AppError;
+use crate::markdown::{heading_ids, link_targets, LineIndex, LinkKind};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BrokenLink {
+    pub url: String,
+    pub reason: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PostLinkReport {
+    pub slug: String,
+    pub title: String,
+    pub broken: Vec<BrokenLink>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LinkCheckReport {
+    pub posts_checked: usize,
+    pub links_checked: usize,
+    /// Only posts with at least one broken link
+    pub posts: Vec<PostLinkReport>,
+}
+
+/// Whether a URL points off-site (or at something we cannot check locally)
+pub(crate) fn is_external(url: &str) -> bool {
+    let lower = url.to_lowercase();
+    lower.starts_with("//")
+        || ["http:", "https:", "mailto:", "tel:", "data:", "javascript:"]
+            .iter()
+            .any(|scheme| lower.starts_with(scheme))
+}
+
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+            if let Some(byte) = hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+/// Resolve `path` against the directory URL `base` into normalized segments
+fn resolve_path(base: &str, path: &str) -> Vec<String> {
+    let joined = if path.starts_with('/') {
+        path.to_string()
+    } else {
+        format!("{}/{}", base.trim_end_matches('/'), path)
+    };
+
+    let mut segments: Vec<String> = Vec::new();
+    for segment in joined.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            s => segments.push(percent_decode(s)),
+        }
+    }
+    segments
+}
+
+/// Anchor ids available in each post: generated heading ids plus explicit
+/// `id="..."` attributes in raw HTML
+fn anchor_ids(posts: &[PostContent]) -> HashMap<&str, HashSet<String>> {
+    let id_re = Regex::new(r#"\bid\s*=\s*["']([^"']+)["']"#).unwrap();
+
+    posts
+        .iter()
+        .map(|post| {
+            let mut ids: HashSet<String> = heading_ids(&post.content).into_iter().collect();
+            ids.extend(id_re.captures_iter(&post.content).map(|cap| cap[1].to_string()));
+            (post.slug.as_str(), ids)
+        })
+        .collect()
+}
+
+/// Whether a site path (already split into segments) exists in the repo,
+/// either as a file under `static/` or as a route under `src/routes/`
+fn site_path_exists(repo: &Path, segments: &[String]) -> bool {
+    let static_path = segments.iter().fold(repo.join("static"), |p, s| p.join(s));
+    if static_path.is_file() {
+        return true;
+    }
+
+    let route_dir = segments.iter().fold(repo.join("src").join("routes"), |p, s| p.join(s));
+    route_dir.is_dir()
+        && ["+page.svelte", "+page.md", "+page.svx", "+page.ts", "+page.server.ts", "+server.ts"]
+            .iter()
+            .any(|f| route_dir.join(f).exists())
+}
+
+/// Check a single internal link from `post`, returning why it is broken
+fn check_link(
+    repo_path: &str,
+    post: &PostContent,
+    kind: LinkKind,
+    url: &str,
+    anchors: &HashMap<&str, HashSet<String>>,
+) -> Option<String> {
+    let (path, fragment) = match url.split_once('#') {
+        Some((path, fragment)) => (path, Some(percent_decode(fragment))),
+        None => (url, None),
+    };
+    let path = path.split('?').next().unwrap_or("");
+
+    // Same-page anchor
+    if path.is_empty() {
+        let fragment = fragment.unwrap_or_default();
+        return match anchors.get(post.slug.as_str()) {
+            Some(ids) if fragment.is_empty() || ids.contains(&fragment) => None,
+            _ => Some(format!("No heading with id \"{}\" in this post", fragment)),
+        };
+    }
+
+    // Relative images may sit next to +page.md, so look in the post's directory
+    // before treating them as site paths
+    if !path.starts_with('/') && kind == LinkKind::Image {
+        let local = resolve_path("", path).iter().fold(blog_dir(repo_path).join(&post.slug), |p, s| p.join(s));
+        if local.is_file() {
+            return None;
+        }
+    }
+
+    // A post lives at `/blog/<slug>`, so relative links resolve against `/blog/`
+    let segments = resolve_path("/blog/", path);
+    let repo = Path::new(repo_path);
+
+    if segments.len() == 2 && segments[0] == "blog" {
+        let target = &segments[1];
+        if !blog_dir(repo_path).join(target).join("+page.md").exists() {
+            return Some(format!("Post \"{}\" does not exist", target));
+        }
+        if let Some(fragment) = fragment.filter(|f| !f.is_empty()) {
+            if !anchors.get(target.as_str()).is_some_and(|ids| ids.contains(&fragment)) {
+                return Some(format!("No heading with id \"{}\" in post \"{}\"", fragment, target));
+            }
+        }
+        return None;
+    }
+
+    if site_path_exists(repo, &segments) {
+        None
+    } else if kind == LinkKind::Image {
+        Some(format!("No file at static/{}", segments.join("/")))
+    } else {
+        Some(format!("Nothing found at /{}", segments.join("/")))
+    }
+}
+
+/// Check every internal link, anchor and image path in every post.
+///
+/// `/blog/<slug>` links must point at an existing post (and heading, if an
+/// anchor is given), other site paths at a file under `static/` or a route
+/// under `src/routes/`. Relative paths resolve against the post's URL, so
+/// `other-post` means `/blog/other-post`; relative images may also sit next to
+/// the post's `+page.md`.
+#[tauri::command]
+pub fn check_links(repo_path: String) -> Result<LinkCheckReport, AppError> {
+    let blog = blog_dir(&repo_path);
+    if !blog.exists() {
+        return Err(AppError::BlogDirNotFound { path: blog });
+    }
+
+    let posts = load_posts(&repo_path)?;
+    let anchors = anchor_ids(&posts);
+    let mut links_checked = 0;
+    let mut reports = Vec::new();
+
+    for post in &posts {
+        let lines = LineIndex::new(&post.content);
+        let mut broken = Vec::new();
+
+        for target in link_targets(&post.content) {
+            if target.url.trim().is_empty() || is_external(&target.url) {
+                continue;
+            }
+            links_checked += 1;
+
+            if let Some(reason) = check_link(&repo_path, post, target.kind, &target.url, &anchors) {
+                let (line, column) = lines.position(target.offset);
+                broken.push(BrokenLink {
+                    url: target.url,
+                    reason,
+                    line,
+                    column,
+                });
+            }
+        }
+
+        if !broken.is_empty() {
+            reports.push(PostLinkReport {
+                slug: post.slug.clone(),
+                title: post.title.clone(),
+                broken,
+            });
+        }
+    }
+
+    Ok(LinkCheckReport {
+        posts_checked: posts.len(),
+        links_checked,
+        posts: reports,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blog::tests::{create, fake_repo};
+    use std::fs;
+
+    #[test]
+    fn relative_links_resolve_against_the_blog() {
+        assert_eq!(resolve_path("/blog/", "./x"), ["blog", "x"]);
+        assert_eq!(resolve_path("/blog/", "x"), ["blog", "x"]);
+        assert_eq!(resolve_path("/blog/", "../x"), ["x"]);
+    }
+
+    #[test]
+    fn relative_links_point_at_posts_routes_and_colocated_images() {
+        let repo = fake_repo("links-relative");
+        let about = Path::new(&repo).join("src/routes/about");
+        fs::create_dir_all(&about).unwrap();
+        fs::write(about.join("+page.svelte"), "").unwrap();
+        create(&repo, "b", "B", "Body");
+        create(
+            &repo,
+            "a",
+            "A",
+            "[one](b) [two](./b) [about](../about) [gone](../b) ![fig](./fig.png) ![nofig](nofig.png)",
+        );
+        fs::write(blog_dir(&repo).join("a").join("fig.png"), "png").unwrap();
+
+        let report = check_links(repo).unwrap();
+        let broken: Vec<&str> = report.posts.iter().flat_map(|p| &p.broken).map(|b| b.url.as_str()).collect();
+        assert_eq!(broken, ["../b", "nofig.png"]);
+    }
+}
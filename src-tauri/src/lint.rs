@@ -0,0 +1,225 @@
+use crate::config::load_config;
+use crate::error::AppError;
+use crate::markdown::{heading_number, parser_options, LineIndex};
+use pulldown_cmark::{Event, Parser, Tag, TagEnd};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LintIssue {
+    pub rule: String,
+    pub severity: Severity,
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Collects issues for one document, dropping rules disabled in config
+struct Linter<'a> {
+    lines: LineIndex<'a>,
+    disabled: &'a [String],
+    issues: Vec<LintIssue>,
+}
+
+impl Linter<'_> {
+    fn report(&mut self, rule: &str, severity: Severity, offset: usize, message: String) {
+        if self.disabled.iter().any(|d| d == rule) {
+            return;
+        }
+        let (line, column) = self.lines.position(offset);
+        self.issues.push(LintIssue {
+            rule: rule.to_string(),
+            severity,
+            message,
+            line,
+            column,
+        });
+    }
+}
+
+fn lint_markdown(content: &str, linter: &mut Linter, max_paragraph_words: usize) {
+    let img_re = Regex::new(r"(?i)<img\b[^>]*>").unwrap();
+    let alt_re = Regex::new(r#"(?i)\balt\s*=\s*["']?[^\s"'>]"#).unwrap();
+
+    // The template renders the title as <h1>, so the body starts at level 1
+    let mut previous_level = 1;
+    let mut in_code_block = false;
+    let mut link_depth = 0;
+    let mut link_start = 0;
+    let mut link_text = String::new();
+    let mut link_has_image = false;
+    let mut image_start = 0;
+    let mut image_alt: Option<String> = None;
+    let mut paragraph_start = 0;
+    let mut paragraph_words = 0;
+
+    for (event, range) in Parser::new_ext(content, parser_options()).into_offset_iter() {
+        match event {
+            Event::Start(Tag::Heading { level, .. }) => {
+                let level = heading_number(level);
+                if level == 1 {
+                    linter.report(
+                        "no-duplicate-title",
+                        Severity::Warning,
+                        range.start,
+                        "Top-level `#` heading duplicates the post title rendered by the template".to_string(),
+                    );
+                } else if level > previous_level + 1 {
+                    linter.report(
+                        "heading-increment",
+                        Severity::Warning,
+                        range.start,
+                        format!("Heading level skips from h{} to h{}", previous_level, level),
+                    );
+                }
+                previous_level = level;
+            }
+            Event::Start(Tag::CodeBlock(_)) => in_code_block = true,
+            Event::End(TagEnd::CodeBlock) => in_code_block = false,
+            Event::Start(Tag::Paragraph) => {
+                paragraph_start = range.start;
+                paragraph_words = 0;
+            }
+            Event::End(TagEnd::Paragraph) if paragraph_words > max_paragraph_words => {
+                linter.report(
+                    "long-paragraph",
+                    Severity::Info,
+                    paragraph_start,
+                    format!(
+                        "Paragraph has {} words (limit {})",
+                        paragraph_words, max_paragraph_words
+                    ),
+                );
+            }
+            Event::Start(Tag::Link { dest_url, .. }) => {
+                link_depth += 1;
+                link_start = range.start;
+                link_text.clear();
+                link_has_image = false;
+                if dest_url.trim().is_empty() {
+                    linter.report(
+                        "empty-link",
+                        Severity::Error,
+                        range.start,
+                        "Link has no target".to_string(),
+                    );
+                }
+            }
+            Event::End(TagEnd::Link) => {
+                link_depth -= 1;
+                if link_text.trim().is_empty() && !link_has_image {
+                    linter.report(
+                        "empty-link",
+                        Severity::Error,
+                        link_start,
+                        "Link has no text".to_string(),
+                    );
+                }
+            }
+            Event::Start(Tag::Image { .. }) => {
+                link_has_image = true;
+                image_start = range.start;
+                image_alt = Some(String::new());
+            }
+            Event::End(TagEnd::Image) => {
+                let alt = image_alt.take().unwrap_or_default();
+                if alt.trim().is_empty() {
+                    linter.report(
+                        "image-alt",
+                        Severity::Warning,
+                        image_start,
+                        "Image has no alt text".to_string(),
+                    );
+                }
+            }
+            Event::Text(text) if !in_code_block => {
+                paragraph_words += text.split_whitespace().count();
+
+                if let Some(alt) = image_alt.as_mut() {
+                    alt.push_str(&text);
+                } else if link_depth > 0 {
+                    link_text.push_str(&text);
+                } else {
+                    let source = &content[range.clone()];
+                    for (i, _) in source.match_indices("http://").chain(source.match_indices("https://")) {
+                        linter.report(
+                            "bare-url",
+                            Severity::Info,
+                            range.start + i,
+                            "Bare URL; wrap it in a link or <...>".to_string(),
+                        );
+                    }
+                }
+            }
+            Event::Code(code) => {
+                paragraph_words += code.split_whitespace().count();
+                if link_depth > 0 {
+                    link_text.push_str(&code);
+                }
+            }
+            Event::Html(html) | Event::InlineHtml(html) => {
+                for tag in img_re.find_iter(&html) {
+                    if !alt_re.is_match(tag.as_str()) {
+                        let offset = content[range.clone()]
+                            .find(tag.as_str())
+                            .map_or(range.start, |i| range.start + i);
+                        linter.report(
+                            "image-alt",
+                            Severity::Warning,
+                            offset,
+                            "<img> has no alt text".to_string(),
+                        );
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn lint_whitespace(content: &str, linter: &mut Linter) {
+    let mut offset = 0;
+    for line in content.split('\n') {
+        let trimmed = line.trim_end_matches([' ', '\t', '\r']);
+        if trimmed.len() < line.trim_end_matches('\r').len() {
+            linter.report(
+                "trailing-whitespace",
+                Severity::Info,
+                offset + trimmed.len(),
+                "Trailing whitespace".to_string(),
+            );
+        }
+        offset += line.len() + 1;
+    }
+}
+
+/// Lint a post body before it is saved.
+///
+/// Rule ids: `heading-increment`, `no-duplicate-title`, `image-alt`,
+/// `empty-link`, `bare-url`, `trailing-whitespace`, `long-paragraph`.
+/// Any of them can be switched off via `lint.disabled_rules` in the config.
+#[tauri::command]
+pub fn lint_post(repo_path: String, content: String) -> Result<Vec<LintIssue>, AppError> {
+    let config = load_config(&repo_path)?;
+
+    let mut linter = Linter {
+        lines: LineIndex::new(&content),
+        disabled: &config.lint.disabled_rules,
+        issues: Vec::new(),
+    };
+
+    lint_markdown(&content, &mut linter, config.lint.max_paragraph_words);
+    lint_whitespace(&content, &mut linter);
+
+    let mut issues = linter.issues;
+    issues.sort_by_key(|issue| (issue.line, issue.column));
+    Ok(issues)
+}
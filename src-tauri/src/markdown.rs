@@ -0,0 +1,300 @@
+use pulldown_cmark::{html, CowStr, Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+use regex::Regex;
+use std::collections::HashMap;
+
+const WORDS_PER_MINUTE: usize = 200;
+
+/// Markdown extensions understood by the site's mdsvex setup
+pub(crate) fn parser_options() -> Options {
+    Options::ENABLE_TABLES
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+}
+
+/// Flatten a Markdown body into plain prose.
+///
+/// Fenced/indented code blocks and raw HTML (including Svelte components such
+/// as `<Citation />`) are dropped; block boundaries become newlines.
+pub(crate) fn plain_text(markdown: &str) -> String {
+    let mut text = String::new();
+    let mut in_code_block = false;
+
+    for event in Parser::new_ext(markdown, parser_options()) {
+        match event {
+            Event::Start(Tag::CodeBlock(_)) => in_code_block = true,
+            Event::End(TagEnd::CodeBlock) => in_code_block = false,
+            Event::Text(t) if !in_code_block => text.push_str(&t),
+            Event::Code(t) => text.push_str(&t),
+            Event::SoftBreak | Event::HardBreak => text.push(' '),
+            Event::End(
+                TagEnd::Paragraph
+                | TagEnd::Heading(_)
+                | TagEnd::Item
+                | TagEnd::TableCell
+                | TagEnd::TableRow
+                | TagEnd::BlockQuote(_),
+            ) => text.push('\n'),
+            _ => {}
+        }
+    }
+
+    text.trim().to_string()
+}
+
+/// Number of prose words in a Markdown body, ignoring code blocks and HTML
+pub(crate) fn word_count(markdown: &str) -> usize {
+    plain_text(markdown)
+        .split_whitespace()
+        .filter(|w| w.chars().any(char::is_alphanumeric))
+        .count()
+}
+
+/// Estimated reading time in whole minutes (at least one for any content)
+pub(crate) fn reading_time(word_count: usize) -> usize {
+    word_count.div_ceil(WORDS_PER_MINUTE)
+}
+
+/// Split text into lowercase word tokens
+pub(crate) fn tokenize(text: &str) -> Vec<String> {
+    word_spans(text)
+        .into_iter()
+        .map(|(start, end)| text[start..end].to_lowercase())
+        .collect()
+}
+
+/// Byte ranges of every word in `text` (runs of alphanumerics, allowing
+/// inner apostrophes like "don't")
+pub(crate) fn word_spans(text: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut start: Option<usize> = None;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        let inner_apostrophe = c == '\''
+            && start.is_some()
+            && chars.peek().is_some_and(|(_, next)| next.is_alphanumeric());
+
+        if c.is_alphanumeric() || inner_apostrophe {
+            start.get_or_insert(i);
+        } else if let Some(s) = start.take() {
+            spans.push((s, i));
+        }
+    }
+
+    if let Some(s) = start {
+        spans.push((s, text.len()));
+    }
+
+    spans
+}
+
+/// Common English words ignored when indexing and ranking
+pub(crate) fn is_stopword(word: &str) -> bool {
+    const STOPWORDS: &[&str] = &[
+        "a", "about", "after", "all", "also", "an", "and", "any", "are", "as", "at", "be",
+        "because", "been", "but", "by", "can", "could", "did", "do", "does", "for", "from",
+        "had", "has", "have", "he", "her", "his", "how", "i", "if", "in", "into", "is", "it",
+        "its", "just", "me", "more", "most", "my", "no", "not", "of", "on", "one", "or",
+        "our", "out", "she", "so", "some", "than", "that", "the", "their", "them", "then",
+        "there", "these", "they", "this", "to", "up", "us", "was", "we", "were", "what",
+        "when", "which", "who", "will", "with", "would", "you", "your",
+    ];
+    STOPWORDS.contains(&word)
+}
+
+pub(crate) fn heading_number(level: HeadingLevel) -> usize {
+    match level {
+        HeadingLevel::H1 => 1,
+        HeadingLevel::H2 => 2,
+        HeadingLevel::H3 => 3,
+        HeadingLevel::H4 => 4,
+        HeadingLevel::H5 => 5,
+        HeadingLevel::H6 => 6,
+    }
+}
+
+/// Slugify heading text the way github-slugger does: lowercase, drop
+/// punctuation, spaces to hyphens
+fn heading_slug(text: &str) -> String {
+    text.trim()
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_'))
+        .map(|c| if c == ' ' { '-' } else { c })
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Heading {
+    pub level: usize,
+    pub text: String,
+    /// Anchor id, as generated by github-slugger/rehype-slug
+    pub id: String,
+}
+
+/// Every heading in a Markdown body with its anchor id; repeated ids get
+/// `-1`, `-2`, ... suffixes in document order
+pub(crate) fn headings(markdown: &str) -> Vec<Heading> {
+    let mut headings = Vec::new();
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    let mut current: Option<String> = None;
+
+    for event in Parser::new_ext(markdown, parser_options()) {
+        match event {
+            Event::Start(Tag::Heading { .. }) => current = Some(String::new()),
+            Event::Text(t) | Event::Code(t) => {
+                if let Some(text) = current.as_mut() {
+                    text.push_str(&t);
+                }
+            }
+            Event::End(TagEnd::Heading(level)) => {
+                if let Some(text) = current.take() {
+                    let base = heading_slug(&text);
+                    let mut id = base.clone();
+                    while seen.contains_key(&id) {
+                        let count = seen.entry(base.clone()).or_insert(0);
+                        *count += 1;
+                        id = format!("{}-{}", base, count);
+                    }
+                    seen.insert(id.clone(), 0);
+                    headings.push(Heading {
+                        level: heading_number(level),
+                        text: text.trim().to_string(),
+                        id,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    headings
+}
+
+/// Anchor ids of every heading in a Markdown body, in document order
+pub(crate) fn heading_ids(markdown: &str) -> Vec<String> {
+    headings(markdown).into_iter().map(|h| h.id).collect()
+}
+
+/// Render a Markdown body to HTML, giving headings the ids the site gives them
+pub(crate) fn render_html(markdown: &str) -> String {
+    let mut ids = heading_ids(markdown).into_iter();
+    let events = Parser::new_ext(markdown, parser_options()).map(|event| match event {
+        Event::Start(Tag::Heading { level, id, classes, attrs }) => Event::Start(Tag::Heading {
+            level,
+            id: ids.next().map(CowStr::from).or(id),
+            classes,
+            attrs,
+        }),
+        event => event,
+    });
+
+    let mut output = String::new();
+    html::push_html(&mut output, events);
+    output
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum LinkKind {
+    Link,
+    Image,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct LinkTarget {
+    pub kind: LinkKind,
+    pub url: String,
+    /// Byte offset of the link in the source
+    pub offset: usize,
+}
+
+/// Every link and image target in a Markdown body, including `href`/`src`
+/// attributes of raw HTML. Code is skipped.
+pub(crate) fn link_targets(markdown: &str) -> Vec<LinkTarget> {
+    let attr_re = Regex::new(r#"(?i)\b(href|src)\s*=\s*["']([^"']+)["']"#).unwrap();
+    let mut targets = Vec::new();
+
+    for (event, range) in Parser::new_ext(markdown, parser_options()).into_offset_iter() {
+        match event {
+            Event::Start(Tag::Link { dest_url, .. }) => targets.push(LinkTarget {
+                kind: LinkKind::Link,
+                url: dest_url.to_string(),
+                offset: range.start,
+            }),
+            Event::Start(Tag::Image { dest_url, .. }) => targets.push(LinkTarget {
+                kind: LinkKind::Image,
+                url: dest_url.to_string(),
+                offset: range.start,
+            }),
+            Event::Html(html) | Event::InlineHtml(html) => {
+                for cap in attr_re.captures_iter(&html) {
+                    let kind = if cap[1].eq_ignore_ascii_case("src") {
+                        LinkKind::Image
+                    } else {
+                        LinkKind::Link
+                    };
+                    let offset = markdown[range.clone()]
+                        .find(&cap[0])
+                        .map_or(range.start, |i| range.start + i);
+                    targets.push(LinkTarget {
+                        kind,
+                        url: cap[2].to_string(),
+                        offset,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    targets
+}
+
+/// Maps byte offsets in a source text to 1-based line/column positions
+pub(crate) struct LineIndex<'a> {
+    text: &'a str,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub(crate) fn new(text: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+        LineIndex { text, line_starts }
+    }
+
+    /// Line and column (in characters) of a byte offset
+    pub(crate) fn position(&self, offset: usize) -> (usize, usize) {
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        let column = self.text[self.line_starts[line]..offset].chars().count();
+        (line + 1, column + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn word_count_skips_code_blocks_and_html() {
+        let markdown = "# Title\n\nSome *prose* here, and `code`.\n\n```rust\nlet x = 1;\n```\n\n<div class=\"note\">\n\n- one item\n";
+        // Inline code reads as part of the sentence, so it counts
+        assert_eq!(word_count(markdown), 8);
+    }
+
+    #[test]
+    fn reading_time_rounds_up() {
+        assert_eq!(reading_time(0), 0);
+        assert_eq!(reading_time(1), 1);
+        assert_eq!(reading_time(WORDS_PER_MINUTE), 1);
+        assert_eq!(reading_time(WORDS_PER_MINUTE + 1), 2);
+    }
+
+    #[test]
+    fn repeated_headings_get_numbered_ids() {
+        let ids = heading_ids("## Intro\n\n## Intro\n\n## Intro 1\n\n### What's `new`?\n");
+        assert_eq!(ids, ["intro", "intro-1", "intro-1-1", "whats-new"]);
+    }
+}
@@ -0,0 +1,167 @@
+use crate::blog::{load_posts, PostContent};
+use crate::config::load_config;
+use crate::error::AppError;
+use crate::markdown::{plain_text, tokenize};
+use crate::summary::is_keyword_candidate;
+use crate::transaction::Transaction;
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Share of the score that comes from shared tags; the rest is text similarity
+const TAG_WEIGHT: f64 = 0.5;
+
+/// Posts scoring below this are not considered related at all
+const MIN_SCORE: f64 = 0.05;
+
+pub(crate) fn related_path(repo_path: &str) -> PathBuf {
+    Path::new(repo_path).join("src").join("lib").join("related.ts")
+}
+
+/// TF-IDF weights of every candidate term of each post
+fn term_weights(posts: &[PostContent]) -> Vec<HashMap<String, f64>> {
+    let term_freqs: Vec<HashMap<String, usize>> = posts
+        .iter()
+        .map(|post| {
+            let mut freqs = HashMap::new();
+            for word in tokenize(&plain_text(&post.content)) {
+                if is_keyword_candidate(&word) {
+                    *freqs.entry(word).or_insert(0) += 1;
+                }
+            }
+            freqs
+        })
+        .collect();
+
+    let mut doc_freqs: HashMap<&str, usize> = HashMap::new();
+    for freqs in &term_freqs {
+        for term in freqs.keys() {
+            *doc_freqs.entry(term).or_insert(0) += 1;
+        }
+    }
+
+    let doc_count = posts.len() as f64;
+    term_freqs
+        .iter()
+        .map(|freqs| {
+            freqs
+                .iter()
+                .map(|(term, &tf)| {
+                    let idf = ((doc_count + 1.0) / (doc_freqs[term.as_str()] as f64 + 1.0)).ln() + 1.0;
+                    (term.clone(), tf as f64 * idf)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn cosine(a: &HashMap<String, f64>, b: &HashMap<String, f64>) -> f64 {
+    let dot: f64 = a.iter().filter_map(|(term, w)| b.get(term).map(|v| w * v)).sum();
+    let norm = |v: &HashMap<String, f64>| v.values().map(|w| w * w).sum::<f64>().sqrt();
+    let norms = norm(a) * norm(b);
+    if norms == 0.0 {
+        0.0
+    } else {
+        dot / norms
+    }
+}
+
+/// Shared tags over all tags of the two posts, ignoring case
+fn tag_overlap(a: &[String], b: &[String]) -> f64 {
+    let a: HashSet<String> = a.iter().map(|t| t.to_lowercase()).collect();
+    let b: HashSet<String> = b.iter().map(|t| t.to_lowercase()).collect();
+    let union = a.union(&b).count();
+    if union == 0 {
+        0.0
+    } else {
+        a.intersection(&b).count() as f64 / union as f64
+    }
+}
+
+/// Up to `count` related posts for each post, best first
+fn related_posts(posts: &[PostContent], count: usize) -> Vec<(&PostContent, Vec<&PostContent>)> {
+    let weights = term_weights(posts);
+
+    posts
+        .iter()
+        .enumerate()
+        .map(|(i, post)| {
+            let mut scored: Vec<(f64, &PostContent)> = posts
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| j != i)
+                .map(|(j, other)| {
+                    let score = TAG_WEIGHT * tag_overlap(&post.tags, &other.tags)
+                        + (1.0 - TAG_WEIGHT) * cosine(&weights[i], &weights[j]);
+                    (score, other)
+                })
+                .filter(|(score, _)| *score >= MIN_SCORE)
+                .collect();
+            scored.sort_by(|(a_score, a), (b_score, b)| {
+                b_score.total_cmp(a_score).then_with(|| a.slug.cmp(&b.slug))
+            });
+            (post, scored.into_iter().take(count).map(|(_, p)| p).collect())
+        })
+        .collect()
+}
+
+fn generate_related_ts(related: &[(&PostContent, Vec<&PostContent>)]) -> String {
+    let mut content = String::from(
+r#"// Auto-generated from the posts' tags and text
+// Run: blog related
+// Do NOT edit manually - it is rewritten whenever a post is saved
+
+export interface RelatedPost {
+    slug: string;
+    title: string;
+}
+
+export const related: Record<string, RelatedPost[]> = {
+"#);
+
+    for (post, others) in related.iter().filter(|(_, others)| !others.is_empty()) {
+        content.push_str(&format!("    {}: [\n", json!(post.slug)));
+        for other in others {
+            content.push_str(&format!(
+                "        {{ slug: {}, title: {} }},\n",
+                json!(other.slug),
+                json!(other.title)
+            ));
+        }
+        content.push_str("    ],\n");
+    }
+
+    content.push_str("};\n");
+    content
+}
+
+/// Stage `related.ts` for `posts` if it differs from what is on disk,
+/// returning its path if so
+pub(crate) fn stage_related(
+    transaction: &mut Transaction,
+    repo_path: &str,
+    posts: &[PostContent],
+) -> Result<Option<PathBuf>, AppError> {
+    let config = load_config(repo_path)?;
+    let content = generate_related_ts(&related_posts(posts, config.related_count));
+
+    let path = related_path(repo_path);
+    if fs::read_to_string(&path).ok().as_deref() == Some(content.as_str()) {
+        return Ok(None);
+    }
+    transaction.write(&path, content);
+    Ok(Some(path))
+}
+
+/// Recompute the related posts of every post and write `src/lib/related.ts`
+#[tauri::command]
+pub fn update_related_posts(repo_path: String) -> Result<String, AppError> {
+    let posts = load_posts(&repo_path)?;
+
+    let mut transaction = Transaction::default();
+    stage_related(&mut transaction, &repo_path, &posts)?;
+    transaction.commit()?;
+
+    Ok(format!("Updated related posts for {} posts", posts.len()))
+}
@@ -0,0 +1,464 @@
+use crate::blog::{blog_dir, parse_post, PostContent};
+use crate::error::AppError;
+use crate::markdown::{is_stopword, plain_text, tokenize, word_spans};
+use crate::seo::escape_xml;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::sync::Mutex;
+use std::time::SystemTime;
+use tauri::State;
+
+/// Title, tags, excerpt and body are weighted in that order
+const FIELD_WEIGHTS: [f64; 4] = [3.0, 2.0, 1.5, 1.0];
+const BM25_K1: f64 = 1.2;
+const BM25_B: f64 = 0.75;
+const MAX_RESULTS: usize = 50;
+const SNIPPET_LEAD: usize = 60;
+const SNIPPET_LENGTH: usize = 200;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchResult {
+    pub slug: String,
+    pub title: String,
+    pub date: String,
+    pub excerpt: String,
+    pub tags: Vec<String>,
+    pub score: f64,
+    /// HTML-escaped excerpt of the body with matches wrapped in `<mark>`
+    pub snippet: String,
+}
+
+/// In-memory full-text index over every post, held in Tauri state.
+///
+/// The index is refreshed before each search: posts whose `+page.md`
+/// modification time or size changed are re-parsed, deleted posts dropped
+/// and new ones added, so edits made outside the app are picked up too.
+#[derive(Default)]
+pub struct SearchIndex {
+    inner: Mutex<IndexState>,
+}
+
+#[derive(Default)]
+struct IndexState {
+    repo_path: String,
+    docs: HashMap<String, IndexedPost>,
+}
+
+struct IndexedPost {
+    stamp: (SystemTime, u64),
+    post: PostContent,
+    body: String,
+    /// Token streams for title, tags, excerpt and body
+    fields: [Vec<String>; 4],
+    /// Field-weighted term frequencies
+    term_freqs: HashMap<String, f64>,
+    length: usize,
+}
+
+impl IndexedPost {
+    fn new(post: PostContent, stamp: (SystemTime, u64)) -> Self {
+        let body = plain_text(&post.content);
+        let fields = [
+            tokenize(&post.title),
+            tokenize(&post.tags.join(" ")),
+            tokenize(&post.excerpt),
+            tokenize(&body),
+        ];
+
+        let mut term_freqs = HashMap::new();
+        for (tokens, weight) in fields.iter().zip(FIELD_WEIGHTS) {
+            for token in tokens {
+                *term_freqs.entry(token.clone()).or_insert(0.0) += weight;
+            }
+        }
+        let length = fields.iter().map(Vec::len).sum();
+
+        IndexedPost {
+            stamp,
+            post,
+            body,
+            fields,
+            term_freqs,
+            length,
+        }
+    }
+
+    fn contains_phrase(&self, phrase: &[String]) -> bool {
+        self.fields
+            .iter()
+            .any(|tokens| tokens.windows(phrase.len()).any(|w| w == phrase))
+    }
+
+    fn has_tag(&self, tag: &str) -> bool {
+        self.post.tags.iter().any(|t| t.to_lowercase() == tag)
+    }
+}
+
+/// A parsed search query: bare terms, "quoted phrases" and `tag:` filters
+#[derive(Debug, Default)]
+struct SearchQuery {
+    terms: Vec<String>,
+    phrases: Vec<Vec<String>>,
+    tags: Vec<String>,
+}
+
+impl SearchQuery {
+    fn is_empty(&self) -> bool {
+        self.terms.is_empty() && self.phrases.is_empty() && self.tags.is_empty()
+    }
+
+    /// Every word that contributes to ranking, phrases included
+    fn scored_terms(&self) -> Vec<&String> {
+        let mut seen = HashSet::new();
+        self.terms
+            .iter()
+            .chain(self.phrases.iter().flatten())
+            .filter(|t| seen.insert(*t))
+            .collect()
+    }
+}
+
+/// Split off the next whitespace-delimited word, or a whole `"quoted string"`
+fn take_word(input: &str) -> (&str, &str) {
+    if let Some(quoted) = input.strip_prefix('"') {
+        return match quoted.find('"') {
+            Some(end) => (&quoted[..end], &quoted[end + 1..]),
+            None => (quoted, ""),
+        };
+    }
+    match input.find(char::is_whitespace) {
+        Some(end) => (&input[..end], &input[end..]),
+        None => (input, ""),
+    }
+}
+
+fn parse_query(query: &str) -> SearchQuery {
+    let mut parsed = SearchQuery::default();
+    let mut rest = query.trim();
+
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("tag:") {
+            let (value, remaining) = take_word(after);
+            let tag = value.trim().to_lowercase();
+            if !tag.is_empty() {
+                parsed.tags.push(tag);
+            }
+            rest = remaining;
+        } else if rest.starts_with('"') {
+            let (value, remaining) = take_word(rest);
+            let words = tokenize(value);
+            if words.len() > 1 {
+                parsed.phrases.push(words);
+            } else {
+                parsed.terms.extend(words);
+            }
+            rest = remaining;
+        } else {
+            let (value, remaining) = take_word(rest);
+            parsed.terms.extend(tokenize(value));
+            rest = remaining;
+        }
+        rest = rest.trim_start();
+    }
+
+    // Keep stopwords only when the query consists of nothing else
+    if parsed.terms.iter().any(|t| !is_stopword(t)) {
+        parsed.terms.retain(|t| !is_stopword(t));
+    }
+    let mut seen = HashSet::new();
+    parsed.terms.retain(|t| seen.insert(t.clone()));
+
+    parsed
+}
+
+fn refresh(state: &mut IndexState, repo_path: &str) -> Result<(), AppError> {
+    if state.repo_path != repo_path {
+        state.docs.clear();
+        state.repo_path = repo_path.to_string();
+    }
+
+    let blog = blog_dir(repo_path);
+    if !blog.exists() {
+        return Err(AppError::BlogDirNotFound { path: blog });
+    }
+
+    let mut seen = HashSet::new();
+    for entry in fs::read_dir(&blog).map_err(|e| AppError::io("list posts", &blog, e))? {
+        let entry = entry.map_err(|e| AppError::io("list posts", &blog, e))?;
+        let slug = entry.file_name().to_string_lossy().to_string();
+        if slug.starts_with('.') {
+            continue;
+        }
+
+        let post_file = entry.path().join("+page.md");
+        let Ok(file_meta) = fs::metadata(&post_file) else {
+            continue;
+        };
+        let stamp = (
+            file_meta.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+            file_meta.len(),
+        );
+        seen.insert(slug.clone());
+
+        if state.docs.get(&slug).is_some_and(|doc| doc.stamp == stamp) {
+            continue;
+        }
+
+        let file_content = fs::read_to_string(&post_file)
+            .map_err(|e| AppError::io("read post", &post_file, e))?;
+        let post = parse_post(slug.clone(), &file_content);
+        state.docs.insert(slug, IndexedPost::new(post, stamp));
+    }
+
+    state.docs.retain(|slug, _| seen.contains(slug));
+    Ok(())
+}
+
+fn rank<'a>(
+    docs: &'a HashMap<String, IndexedPost>,
+    query: &SearchQuery,
+) -> Vec<(f64, &'a IndexedPost)> {
+    let doc_count = docs.len() as f64;
+    let avg_length = docs.values().map(|d| d.length as f64).sum::<f64>() / doc_count.max(1.0);
+    let terms = query.scored_terms();
+
+    let idf: HashMap<&String, f64> = terms
+        .iter()
+        .map(|term| {
+            let df = docs.values().filter(|d| d.term_freqs.contains_key(*term)).count() as f64;
+            (*term, (1.0 + (doc_count - df + 0.5) / (df + 0.5)).ln())
+        })
+        .collect();
+
+    let mut ranked = Vec::new();
+    for doc in docs.values() {
+        if !query.tags.iter().all(|tag| doc.has_tag(tag)) {
+            continue;
+        }
+        if !query.phrases.iter().all(|phrase| doc.contains_phrase(phrase)) {
+            continue;
+        }
+
+        let length_norm = 1.0 - BM25_B + BM25_B * doc.length as f64 / avg_length.max(1.0);
+        let score: f64 = terms
+            .iter()
+            .map(|term| {
+                let tf = doc.term_freqs.get(*term).copied().unwrap_or(0.0);
+                idf[term] * tf * (BM25_K1 + 1.0) / (tf + BM25_K1 * length_norm)
+            })
+            .sum();
+
+        if !query.terms.is_empty() && query.phrases.is_empty() && score == 0.0 {
+            continue;
+        }
+        ranked.push((score, doc));
+    }
+
+    ranked.sort_by(|(a_score, a), (b_score, b)| {
+        b_score
+            .total_cmp(a_score)
+            .then_with(|| b.post.date.cmp(&a.post.date))
+    });
+    ranked
+}
+
+fn floor_char_boundary(text: &str, mut index: usize) -> usize {
+    index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+/// Byte ranges in `text` matching any query term or phrase
+fn match_ranges(text: &str, query: &SearchQuery) -> Vec<(usize, usize)> {
+    let spans = word_spans(text);
+    let words: Vec<String> = spans.iter().map(|&(s, e)| text[s..e].to_lowercase()).collect();
+    let mut ranges = Vec::new();
+
+    for (i, word) in words.iter().enumerate() {
+        if query.terms.contains(word) {
+            ranges.push(spans[i]);
+        }
+        for phrase in &query.phrases {
+            if words[i..].starts_with(phrase) {
+                ranges.push((spans[i].0, spans[i + phrase.len() - 1].1));
+            }
+        }
+    }
+
+    ranges.sort();
+    let mut merged: Vec<(usize, usize)> = Vec::new();
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+/// Cut a window of `text` around the first match and highlight every match in it
+fn highlight(text: &str, ranges: &[(usize, usize)]) -> String {
+    let mut start = 0;
+    if let Some(&(first, _)) = ranges.first() {
+        if first > SNIPPET_LEAD {
+            let lead = floor_char_boundary(text, first - SNIPPET_LEAD);
+            start = text[lead..first]
+                .find(char::is_whitespace)
+                .map(|i| lead + i + 1)
+                .unwrap_or(lead);
+        }
+    }
+    let mut end = floor_char_boundary(text, start + SNIPPET_LENGTH);
+    if end < text.len() {
+        if let Some(space) = text[start..end].rfind(char::is_whitespace) {
+            end = start + space;
+        }
+    }
+
+    let mut snippet = String::new();
+    if start > 0 {
+        snippet.push('…');
+    }
+    let mut cursor = start;
+    for &(match_start, match_end) in ranges {
+        if match_end <= start || match_start >= end {
+            continue;
+        }
+        let (match_start, match_end) = (match_start.max(start), match_end.min(end));
+        snippet.push_str(&escape_xml(&text[cursor..match_start]));
+        snippet.push_str("<mark>");
+        snippet.push_str(&escape_xml(&text[match_start..match_end]));
+        snippet.push_str("</mark>");
+        cursor = match_end;
+    }
+    snippet.push_str(&escape_xml(&text[cursor..end]));
+    if end < text.len() {
+        snippet.push('…');
+    }
+
+    snippet.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn snippet(doc: &IndexedPost, query: &SearchQuery) -> String {
+    for text in [&doc.body, &doc.post.excerpt] {
+        let ranges = match_ranges(text, query);
+        if !ranges.is_empty() {
+            return highlight(text, &ranges);
+        }
+    }
+    let fallback = if doc.post.excerpt.is_empty() { &doc.body } else { &doc.post.excerpt };
+    highlight(fallback, &[])
+}
+
+/// Search post titles, tags, excerpts and bodies.
+///
+/// Supports bare terms, `"exact phrases"` and `tag:name` filters (quote tags
+/// containing spaces: `tag:"machine learning"`). Results are ranked with
+/// BM25, newest first on ties.
+#[tauri::command]
+pub fn search_posts(
+    repo_path: String,
+    query: String,
+    index: State<'_, SearchIndex>,
+) -> Result<Vec<SearchResult>, AppError> {
+    let parsed = parse_query(&query);
+    if parsed.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut state = index.inner.lock().map_err(|e| AppError::Other { message: e.to_string() })?;
+    refresh(&mut state, &repo_path)?;
+
+    Ok(rank(&state.docs, &parsed)
+        .into_iter()
+        .take(MAX_RESULTS)
+        .map(|(score, doc)| SearchResult {
+            slug: doc.post.slug.clone(),
+            title: doc.post.title.clone(),
+            date: doc.post.date.clone(),
+            excerpt: doc.post.excerpt.clone(),
+            tags: doc.post.tags.clone(),
+            score,
+            snippet: snippet(doc, &parsed),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blog::tests::{create, fake_repo};
+
+    #[test]
+    fn queries_split_into_terms_phrases_and_tags() {
+        let query = parse_query(r#"Rust "proof and progress" tag:Math tag:"machine learning" the"#);
+        assert_eq!(query.terms, ["rust"]);
+        assert_eq!(query.phrases, [["proof", "and", "progress"]]);
+        assert_eq!(query.tags, ["math", "machine learning"]);
+
+        // A one-word phrase is just a term, and stopwords stay when alone
+        assert_eq!(parse_query(r#""proof" proof"#).terms, ["proof"]);
+        assert_eq!(parse_query("the").terms, ["the"]);
+        assert!(parse_query("tag:").is_empty());
+    }
+
+    #[test]
+    fn ranking_prefers_titles_and_filters_by_phrase_and_tag() {
+        let repo = fake_repo("search-rank");
+        create(&repo, "body", "Notes", "Something about borrowing, once.");
+        create(&repo, "title", "Borrowing", "A post on references and lifetimes.");
+        create(&repo, "none", "Other", "Nothing relevant here.");
+
+        let mut state = IndexState::default();
+        refresh(&mut state, &repo).unwrap();
+        let slugs = |query: &str| -> Vec<String> {
+            rank(&state.docs, &parse_query(query)).into_iter().map(|(_, d)| d.post.slug.clone()).collect()
+        };
+
+        assert_eq!(slugs("borrowing"), ["title", "body"]);
+        assert_eq!(slugs(r#""references and lifetimes""#), ["title"]);
+        assert_eq!(slugs("borrowing tag:missing"), Vec::<String>::new());
+        assert_eq!(slugs("tag:tag").len(), 3);
+    }
+
+    #[test]
+    fn snippets_mark_every_match() {
+        let query = parse_query(r#"proof "in mathematics""#);
+        let text = "On proof and progress in mathematics, and <more> proof.";
+        let ranges = match_ranges(text, &query);
+        assert_eq!(ranges.iter().map(|&(s, e)| &text[s..e]).collect::<Vec<_>>(), ["proof", "in mathematics", "proof"]);
+        assert_eq!(
+            highlight(text, &ranges),
+            "On <mark>proof</mark> and progress <mark>in mathematics</mark>, and &lt;more&gt; <mark>proof</mark>."
+        );
+
+        // A late match is shown with a lead-in and ellipses
+        let long = format!("{} needle {}", "word ".repeat(40), "word ".repeat(60));
+        let snippet = highlight(&long, &match_ranges(&long, &parse_query("needle")));
+        assert!(snippet.starts_with('…') && snippet.ends_with('…'));
+        assert!(snippet.contains("<mark>needle</mark>"));
+    }
+
+    #[test]
+    fn refresh_follows_the_files() {
+        let repo = fake_repo("search-refresh");
+        create(&repo, "a", "A", "First");
+        create(&repo, "b", "B", "Second");
+
+        let mut state = IndexState::default();
+        refresh(&mut state, &repo).unwrap();
+        assert_eq!(state.docs.len(), 2);
+
+        let post_file = blog_dir(&repo).join("a").join("+page.md");
+        let edited = fs::read_to_string(&post_file).unwrap().replace("First", "Rewritten outside");
+        fs::write(&post_file, edited).unwrap();
+        fs::remove_dir_all(blog_dir(&repo).join("b")).unwrap();
+
+        refresh(&mut state, &repo).unwrap();
+        assert_eq!(state.docs.len(), 1);
+        assert!(state.docs["a"].body.contains("Rewritten outside"));
+    }
+}
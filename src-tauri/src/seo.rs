@@ -0,0 +1,115 @@
+use crate::bibtex::{read_bib_file, reference_key, BibEntry};
+use crate::config::BlogConfig;
+use regex::Regex;
+use serde_json::{json, Value};
+
+/// Absolute URL of a post, if the site URL is configured
+pub(crate) fn canonical_url(config: &BlogConfig, slug: &str) -> Option<String> {
+    let site_url = config.site_url.trim().trim_end_matches('/');
+    if site_url.is_empty() {
+        None
+    } else {
+        Some(format!("{}/blog/{}", site_url, slug))
+    }
+}
+
+/// Escape text for XML or HTML content and quoted attribute values
+pub(crate) fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Escape text for a literal attribute value in the generated Svelte markup
+pub(crate) fn escape_attr(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('{', "&#123;")
+        .replace('}', "&#125;")
+}
+
+/// A JS literal for `value`, safe to emit inside the generated `<script>` blocks
+pub(crate) fn js_literal(value: Value) -> String {
+    value.to_string().replace('<', "\\u003c")
+}
+
+/// Keys of every `<Citation key="..." />` in a post, in order of first use
+pub(crate) fn cited_keys(content: &str) -> Vec<String> {
+    let citation_re = Regex::new(
+        r#"<Citation\b[^>]*?\bkey\s*=\s*(?:"([^"]+)"|'([^']+)'|\{\s*["']([^"']+)["']\s*\})"#,
+    )
+    .unwrap();
+
+    let mut keys: Vec<String> = Vec::new();
+    for cap in citation_re.captures_iter(content) {
+        let key = cap.get(1).or(cap.get(2)).or(cap.get(3)).unwrap().as_str().to_string();
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+    keys
+}
+
+/// "Last, First and Other, Name" -> schema.org `Person` objects
+fn bib_authors(author: &str) -> Vec<Value> {
+    author
+        .split(" and ")
+        .map(|name| match name.split_once(',') {
+            Some((last, first)) => format!("{} {}", first.trim(), last.trim()),
+            None => name.trim().to_string(),
+        })
+        .filter(|name| !name.is_empty())
+        .map(|name| json!({ "@type": "Person", "name": name }))
+        .collect()
+}
+
+/// schema.org `CreativeWork` describing a bibliography entry
+fn citation_json(entry: &BibEntry) -> Value {
+    let schema_type = match entry.entry_type.to_lowercase().as_str() {
+        "article" | "inproceedings" | "incollection" | "phdthesis" | "mastersthesis" => "ScholarlyArticle",
+        "book" => "Book",
+        "online" | "misc" if entry.url.is_some() => "WebPage",
+        _ => "CreativeWork",
+    };
+
+    let mut citation = json!({
+        "@type": schema_type,
+        "name": entry.title,
+        "author": bib_authors(&entry.author),
+    });
+    if !entry.year.is_empty() {
+        citation["datePublished"] = json!(entry.year);
+    }
+    if let Some(url) = &entry.url {
+        citation["url"] = json!(url);
+    }
+    if let Some(container) = entry.journal.as_ref().or(entry.booktitle.as_ref()) {
+        citation["isPartOf"] = json!({ "@type": "CreativeWork", "name": container });
+    }
+    if let Some(publisher) = &entry.publisher {
+        citation["publisher"] = json!({ "@type": "Organization", "name": publisher });
+    }
+    citation
+}
+
+/// JSON-LD `citation` entries for the references a post cites. Keys may be
+/// given either as in `references.bib` or as in the generated `references.ts`;
+/// unknown keys (or a missing bib file) are skipped.
+pub(crate) fn citations(repo_path: &str, content: &str) -> Vec<Value> {
+    let keys = cited_keys(content);
+    if keys.is_empty() {
+        return Vec::new();
+    }
+
+    let entries = read_bib_file(repo_path.to_string()).unwrap_or_default();
+    keys.iter()
+        .filter_map(|key| {
+            entries
+                .iter()
+                .find(|e| &e.key == key || &reference_key(&e.key) == key)
+        })
+        .map(citation_json)
+        .collect()
+}
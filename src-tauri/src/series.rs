@@ -0,0 +1,386 @@
+use crate::blog::{blog_dir, load_post_files, parse_post, set_metadata_field, PostContent};
+use crate::error::AppError;
+use crate::history::record_current;
+use crate::related::stage_related;
+use crate::seo::{escape_attr, js_literal};
+use crate::transaction::Transaction;
+use serde::Serialize;
+use serde_json::json;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+/// Where the table of contents goes, and where the previous/next links go
+const TOC_BEFORE: &str = "        <div class=\"post-content\">";
+const LINKS_BEFORE: &str = "        <footer class=\"post-footer\">";
+
+const TOC_START: &str = "        <nav class=\"series-nav\"";
+const LINKS_START: &str = "        <nav class=\"series-links\"";
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SeriesPart {
+    pub slug: String,
+    pub title: String,
+    pub order: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Series {
+    pub name: String,
+    /// In reading order
+    pub parts: Vec<SeriesPart>,
+}
+
+/// Parts of the series `name`, by `seriesOrder`, then date for parts without one
+fn series_parts(posts: &[PostContent], name: &str) -> Vec<SeriesPart> {
+    let mut parts: Vec<&PostContent> = posts.iter().filter(|p| p.series == name).collect();
+    parts.sort_by(|a, b| {
+        a.series_order
+            .unwrap_or(u32::MAX)
+            .cmp(&b.series_order.unwrap_or(u32::MAX))
+            .then_with(|| a.date.cmp(&b.date))
+            .then_with(|| a.slug.cmp(&b.slug))
+    });
+    parts
+        .into_iter()
+        .map(|p| SeriesPart {
+            slug: p.slug.clone(),
+            title: p.title.clone(),
+            order: p.series_order,
+        })
+        .collect()
+}
+
+/// Table of contents for the part `slug`, and its previous/next links
+fn series_nav(name: &str, parts: &[SeriesPart], slug: &str) -> (String, String) {
+    let index = parts.iter().position(|p| p.slug == slug).unwrap_or(0);
+    let link = |part: &SeriesPart| format!("/blog/{}", part.slug);
+
+    let mut toc = format!(
+        "{} aria-label=\"Series\">\n            <p>Part {} of {} in <strong>{}</strong></p>\n            <ol>\n",
+        TOC_START,
+        index + 1,
+        parts.len(),
+        escape_attr(name)
+    );
+    for part in parts {
+        if part.slug == slug {
+            toc.push_str(&format!("                <li aria-current=\"page\">{}</li>\n", escape_attr(&part.title)));
+        } else {
+            toc.push_str(&format!("                <li><a href=\"{}\">{}</a></li>\n", link(part), escape_attr(&part.title)));
+        }
+    }
+    toc.push_str("            </ol>\n        </nav>\n");
+
+    let mut links = format!("{} aria-label=\"Series navigation\">\n", LINKS_START);
+    if let Some(prev) = index.checked_sub(1).and_then(|i| parts.get(i)) {
+        links.push_str(&format!(
+            "            <a class=\"series-prev\" href=\"{}\">← {}</a>\n",
+            link(prev),
+            escape_attr(&prev.title)
+        ));
+    }
+    if let Some(next) = parts.get(index + 1) {
+        links.push_str(&format!(
+            "            <a class=\"series-next\" href=\"{}\">{} →</a>\n",
+            link(next),
+            escape_attr(&next.title)
+        ));
+    }
+    links.push_str("        </nav>\n");
+
+    (toc, links)
+}
+
+/// Replace the block starting with `start` by `block`, inserting it before
+/// `before` if the file has none yet. An empty `block` removes it.
+fn replace_block(file_content: &str, start: &str, before: &str, block: &str) -> String {
+    if let Some(block_start) = file_content.find(start) {
+        let block_end = file_content[block_start..]
+            .find("</nav>\n")
+            .map(|i| block_start + i + "</nav>\n".len())
+            .unwrap_or(file_content.len());
+        return format!("{}{}{}", &file_content[..block_start], block, &file_content[block_end..]);
+    }
+
+    match file_content.find(before) {
+        Some(at) if !block.is_empty() => format!("{}{}{}", &file_content[..at], block, &file_content[at..]),
+        _ => file_content.to_string(),
+    }
+}
+
+/// The post file with its series navigation replaced by `nav`, or removed
+pub(crate) fn with_series_nav(file_content: &str, nav: Option<&(String, String)>) -> String {
+    let (toc, links) = nav.map(|(t, l)| (t.as_str(), l.as_str())).unwrap_or(("", ""));
+    let file_content = replace_block(file_content, TOC_START, TOC_BEFORE, toc);
+    replace_block(&file_content, LINKS_START, LINKS_BEFORE, links)
+}
+
+/// `file_content` with the series navigation it would get if `post` were saved
+pub(crate) fn with_current_series_nav(repo_path: &str, post: &PostContent, file_content: &str) -> Result<String, AppError> {
+    if post.series.is_empty() {
+        return Ok(file_content.to_string());
+    }
+
+    let mut posts: Vec<PostContent> = load_post_files(repo_path)?
+        .into_iter()
+        .filter(|(slug, _)| *slug != post.slug)
+        .map(|(slug, content)| parse_post(slug, &content))
+        .collect();
+    posts.push(post.clone());
+
+    let nav = series_nav(&post.series, &series_parts(&posts, &post.series), &post.slug);
+    Ok(with_series_nav(file_content, Some(&nav)))
+}
+
+/// Stage post writes (`None` for a post being deleted), along with the series
+/// navigation of every part of the series they leave or join, so that all
+/// parts stay in step, and the related posts they change. Returns the path
+/// of every file staged.
+pub(crate) fn stage_posts(
+    transaction: &mut Transaction,
+    repo_path: &str,
+    changes: Vec<(String, Option<String>)>,
+) -> Result<Vec<PathBuf>, AppError> {
+    let mut files: BTreeMap<String, String> = load_post_files(repo_path)?.into_iter().collect();
+    let mut affected = BTreeSet::new();
+    let mut changed = BTreeSet::new();
+
+    for (slug, content) in changes {
+        if let Some(old) = files.get(&slug) {
+            affected.insert(parse_post(slug.clone(), old).series);
+        }
+        match content {
+            Some(content) => {
+                affected.insert(parse_post(slug.clone(), &content).series);
+                files.insert(slug.clone(), content);
+                changed.insert(slug);
+            }
+            None => {
+                files.remove(&slug);
+            }
+        }
+    }
+    affected.remove("");
+
+    let posts: Vec<PostContent> = files
+        .iter()
+        .map(|(slug, content)| parse_post(slug.clone(), content))
+        .collect();
+    let parts: BTreeMap<String, Vec<SeriesPart>> = affected
+        .into_iter()
+        .map(|name| {
+            let parts = series_parts(&posts, &name);
+            (name, parts)
+        })
+        .collect();
+
+    let mut written = Vec::new();
+    for post in &posts {
+        let nav = parts
+            .get(&post.series)
+            .map(|parts| series_nav(&post.series, parts, &post.slug));
+        if nav.is_none() && !changed.contains(&post.slug) {
+            continue;
+        }
+
+        let file_content = &files[&post.slug];
+        let new_content = with_series_nav(file_content, nav.as_ref());
+        if changed.contains(&post.slug) || new_content != *file_content {
+            let post_file = blog_dir(repo_path).join(&post.slug).join("+page.md");
+            transaction.write(&post_file, new_content);
+            written.push(post_file);
+        }
+    }
+
+    written.extend(stage_related(transaction, repo_path, &posts)?);
+    Ok(written)
+}
+
+/// Set the series fields of `slug`'s file, or remove them
+fn assign(
+    files: &BTreeMap<String, String>,
+    repo_path: &str,
+    slug: &str,
+    series: Option<(&str, u32)>,
+) -> Result<(String, Option<String>), AppError> {
+    let post_file = blog_dir(repo_path).join(slug).join("+page.md");
+    let file_content = files.get(slug).ok_or_else(|| AppError::PostNotFound {
+        slug: slug.to_string(),
+        path: post_file.clone(),
+    })?;
+
+    let name = series.map(|(name, _)| js_literal(json!(name)));
+    let order = series.map(|(_, order)| order.to_string());
+    let new_content = set_metadata_field(&post_file, file_content, "series", name.as_deref())
+        .and_then(|c| set_metadata_field(&post_file, &c, "seriesOrder", order.as_deref()))?;
+    Ok((slug.to_string(), Some(new_content)))
+}
+
+/// Write validated series changes, keeping the versions they replace in each
+/// post's history
+fn commit_changes(repo_path: &str, changes: Vec<(String, Option<String>)>) -> Result<(), AppError> {
+    let slugs: Vec<String> = changes.iter().map(|(slug, _)| slug.clone()).collect();
+    let mut transaction = Transaction::default();
+    stage_posts(&mut transaction, repo_path, changes)?;
+
+    for slug in &slugs {
+        record_current(repo_path, slug);
+    }
+    transaction.commit()?;
+    Ok(())
+}
+
+fn validate_name(name: &str) -> Result<String, AppError> {
+    let name = name.trim();
+    if name.is_empty() || name.contains(['"', '\\', '\n']) {
+        return Err(AppError::invalid(format!("Invalid series name: {:?}", name)));
+    }
+    Ok(name.to_string())
+}
+
+/// List every series with its parts in order
+#[tauri::command]
+pub fn list_series(repo_path: String) -> Result<Vec<Series>, AppError> {
+    let posts: Vec<PostContent> = load_post_files(&repo_path)?
+        .into_iter()
+        .map(|(slug, content)| parse_post(slug, &content))
+        .collect();
+    let names: BTreeSet<&str> = posts
+        .iter()
+        .map(|p| p.series.as_str())
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    Ok(names
+        .into_iter()
+        .map(|name| Series {
+            name: name.to_string(),
+            parts: series_parts(&posts, name),
+        })
+        .collect())
+}
+
+/// Start a series made of `slugs`, in that order. Posts already in another
+/// series are moved out of it.
+#[tauri::command]
+pub fn create_series(repo_path: String, name: String, slugs: Vec<String>) -> Result<String, AppError> {
+    let name = validate_name(&name)?;
+    let files: BTreeMap<String, String> = load_post_files(&repo_path)?.into_iter().collect();
+    if files.iter().any(|(slug, content)| parse_post(slug.clone(), content).series == name) {
+        return Err(AppError::invalid(format!("Series already exists: {}", name)));
+    }
+    if slugs.is_empty() {
+        return Err(AppError::invalid("A series needs at least one post"));
+    }
+
+    let mut changes = Vec::new();
+    for (i, slug) in slugs.iter().enumerate() {
+        if slugs[..i].contains(slug) {
+            return Err(AppError::invalid(format!("Post listed twice: {}", slug)));
+        }
+        changes.push(assign(&files, &repo_path, slug, Some((&name, i as u32 + 1)))?);
+    }
+
+    commit_changes(&repo_path, changes)?;
+
+    Ok(format!("Created series: {} with {} parts", name, slugs.len()))
+}
+
+/// Put the parts of a series in the order given; `slugs` must list every
+/// part exactly once
+#[tauri::command]
+pub fn reorder_series(repo_path: String, name: String, slugs: Vec<String>) -> Result<String, AppError> {
+    let files: BTreeMap<String, String> = load_post_files(&repo_path)?.into_iter().collect();
+    let posts: Vec<PostContent> = files
+        .iter()
+        .map(|(slug, content)| parse_post(slug.clone(), content))
+        .collect();
+    let parts = series_parts(&posts, &name);
+    if parts.is_empty() {
+        return Err(AppError::invalid(format!("Series not found: {}", name)));
+    }
+
+    let current: BTreeSet<&String> = parts.iter().map(|p| &p.slug).collect();
+    let given: BTreeSet<&String> = slugs.iter().collect();
+    if given != current || slugs.len() != parts.len() {
+        return Err(AppError::invalid(format!(
+            "The new order must list every part of {} exactly once",
+            name
+        )));
+    }
+
+    let mut changes = Vec::new();
+    for (i, slug) in slugs.iter().enumerate() {
+        let order = i as u32 + 1;
+        if posts.iter().any(|p| &p.slug == slug && p.series_order != Some(order)) {
+            changes.push(assign(&files, &repo_path, slug, Some((&name, order)))?);
+        }
+    }
+
+    commit_changes(&repo_path, changes)?;
+
+    Ok(format!("Reordered series: {}", name))
+}
+
+/// Add a post to the end of a series (creating it if needed), or take it out
+/// of its series when `series` is empty
+#[tauri::command]
+pub fn set_post_series(repo_path: String, slug: String, series: Option<String>) -> Result<String, AppError> {
+    let files: BTreeMap<String, String> = load_post_files(&repo_path)?.into_iter().collect();
+    let posts: Vec<PostContent> = files
+        .iter()
+        .map(|(slug, content)| parse_post(slug.clone(), content))
+        .collect();
+    let current = posts.iter().find(|p| p.slug == slug).map(|p| p.series.clone());
+
+    let series = series.filter(|s| !s.trim().is_empty());
+    let (change, message) = match series {
+        Some(name) => {
+            let name = validate_name(&name)?;
+            if current.as_deref() == Some(name.as_str()) {
+                return Ok(format!("{} is already part of {}", slug, name));
+            }
+            let last = series_parts(&posts, &name)
+                .iter()
+                .filter_map(|p| p.order)
+                .max()
+                .unwrap_or(0);
+            let message = format!("Added {} to series {}", slug, name);
+            (assign(&files, &repo_path, &slug, Some((&name, last + 1)))?, message)
+        }
+        None => (
+            assign(&files, &repo_path, &slug, None)?,
+            format!("Removed {} from its series", slug),
+        ),
+    };
+
+    commit_changes(&repo_path, vec![change])?;
+
+    Ok(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blog::open_post_for_edit;
+    use crate::blog::tests::{create, fake_repo};
+    use crate::history::list_revisions;
+
+    #[test]
+    fn rejected_assignments_leave_history_alone() {
+        let repo = fake_repo("series-history");
+        create(&repo, "a", "Part A", "Body");
+        create(&repo, "b", "Part B", "Body");
+        let revisions = |slug: &str| list_revisions(repo.clone(), slug.into()).unwrap().len();
+
+        assert!(create_series(repo.clone(), "Intro".into(), vec!["a".into(), "missing".into()]).is_err());
+        assert!(create_series(repo.clone(), "Intro".into(), vec!["a".into(), "a".into()]).is_err());
+        assert_eq!(revisions("a"), 1);
+
+        create_series(repo.clone(), "Intro".into(), vec!["a".into(), "b".into()]).unwrap();
+        open_post_for_edit(repo.clone(), "a".into()).unwrap();
+        assert_eq!(revisions("a"), 2);
+        assert!(reorder_series(repo.clone(), "Intro".into(), vec!["b".into()]).is_err());
+        assert_eq!(revisions("a"), 2);
+    }
+}
@@ -0,0 +1,217 @@
+use crate::assets::asset_dir;
+use crate::blog::{read_post, set_post_image, PostContent};
+use crate::config::{load_config, AssetLocation, BlogConfig};
+use crate::error::AppError;
+use crate::seo::{canonical_url, escape_xml};
+use crate::transaction::Transaction;
+use resvg::{tiny_skia, usvg};
+
+pub(crate) const CARD_FILE_NAME: &str = "og.png";
+const CARD_WIDTH: u32 = 1200;
+const CARD_HEIGHT: u32 = 630;
+const MARGIN: f32 = 80.0;
+const MAX_TITLE_LINES: usize = 3;
+const MAX_TAGS: usize = 4;
+/// Vertical band (top, bottom) available to the title
+const TITLE_AREA: (f32, f32) = (150.0, 420.0);
+
+/// Average advance of a DejaVu Sans glyph, as a fraction of the font size.
+/// SVG text does not wrap, so lines are broken using this estimate.
+const CHAR_WIDTH: f32 = 0.6;
+
+const FONT_REGULAR: &[u8] = include_bytes!("../fonts/DejaVuSans.ttf");
+const FONT_BOLD: &[u8] = include_bytes!("../fonts/DejaVuSans-Bold.ttf");
+
+fn text_width(text: &str, font_size: f32) -> f32 {
+    text.chars().count() as f32 * font_size * CHAR_WIDTH
+}
+
+/// Greedy word wrap to `max_width`, ending the last allowed line with an
+/// ellipsis if the text does not fit
+fn wrap_text(text: &str, font_size: f32, max_width: f32, max_lines: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut current = String::new();
+
+    for word in text.split_whitespace() {
+        let candidate = if current.is_empty() {
+            word.to_string()
+        } else {
+            format!("{} {}", current, word)
+        };
+
+        if text_width(&candidate, font_size) <= max_width || current.is_empty() {
+            current = candidate;
+        } else {
+            lines.push(std::mem::replace(&mut current, word.to_string()));
+        }
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+
+    if lines.len() > max_lines {
+        lines.truncate(max_lines);
+        let last = &mut lines[max_lines - 1];
+        let max_chars = (max_width / (font_size * CHAR_WIDTH)) as usize;
+        let kept: String = last.chars().take(max_chars.saturating_sub(1)).collect();
+        *last = format!("{}…", kept.trim_end());
+    }
+    lines
+}
+
+/// Title lines and font size: the largest size whose wrapped title fits
+fn layout_title(title: &str) -> (Vec<String>, f32) {
+    let max_width = CARD_WIDTH as f32 - 2.0 * MARGIN;
+    for font_size in [72.0, 64.0, 56.0] {
+        let lines = wrap_text(title, font_size, max_width, usize::MAX);
+        if lines.len() <= MAX_TITLE_LINES {
+            return (lines, font_size);
+        }
+    }
+    (wrap_text(title, 56.0, max_width, MAX_TITLE_LINES), 56.0)
+}
+
+fn format_date(date: &str) -> String {
+    chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map(|d| d.format("%B %-d, %Y").to_string())
+        .unwrap_or_else(|_| date.to_string())
+}
+
+/// SVG source of the card for a post
+fn card_svg(post: &PostContent, site_name: &str) -> String {
+    let (title_lines, title_size) = layout_title(&post.title);
+    // Centre the title block between the site name and the date
+    let line_height = title_size * 1.2;
+    let block_height = title_lines.len() as f32 * line_height;
+    let title_top = TITLE_AREA.0 + (TITLE_AREA.1 - TITLE_AREA.0 - block_height) / 2.0 + title_size;
+
+    let title = title_lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            format!(
+                r#"<tspan x="{}" y="{}">{}</tspan>"#,
+                MARGIN,
+                title_top + i as f32 * line_height,
+                escape_xml(line)
+            )
+        })
+        .collect::<String>();
+
+    let mut tags = String::new();
+    let mut x = MARGIN;
+    for tag in post.tags.iter().take(MAX_TAGS) {
+        let width = text_width(tag, 26.0) + 40.0;
+        if x + width > CARD_WIDTH as f32 - MARGIN {
+            break;
+        }
+        tags.push_str(&format!(
+            r##"<rect x="{}" y="494" width="{}" height="48" rx="8" fill="#3b82f6" fill-opacity="0.15"/><text x="{}" y="527" font-size="26" fill="#60a5fa">{}</text>"##,
+            x,
+            width,
+            x + 20.0,
+            escape_xml(tag)
+        ));
+        x += width + 16.0;
+    }
+
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">
+    <defs>
+        <linearGradient id="bg" x1="0" y1="0" x2="1" y2="1">
+            <stop offset="0" stop-color="#0a0a0f"/>
+            <stop offset="1" stop-color="#111827"/>
+        </linearGradient>
+    </defs>
+    <rect width="{w}" height="{h}" fill="url(#bg)"/>
+    <rect width="{w}" height="12" fill="#3b82f6"/>
+    <g font-family="DejaVu Sans">
+        <text x="{m}" y="118" font-size="32" font-weight="bold" fill="#3b82f6">{site}</text>
+        <text font-size="{ts}" font-weight="bold" fill="#f8fafc">{title}</text>
+        <text x="{m}" y="460" font-size="30" fill="#94a3b8">{date}</text>
+        {tags}
+    </g>
+</svg>"##,
+        w = CARD_WIDTH,
+        h = CARD_HEIGHT,
+        m = MARGIN,
+        site = escape_xml(site_name),
+        ts = title_size,
+        title = title,
+        date = escape_xml(&format_date(&post.date)),
+        tags = tags,
+    )
+}
+
+/// Rasterize the post's card to PNG bytes using only the bundled fonts
+pub(crate) fn render_social_card(post: &PostContent, site_name: &str) -> Result<Vec<u8>, AppError> {
+    let failed = |message: String| AppError::Other { message };
+
+    let mut options = usvg::Options {
+        font_family: "DejaVu Sans".to_string(),
+        ..Default::default()
+    };
+    options.fontdb_mut().load_font_data(FONT_REGULAR.to_vec());
+    options.fontdb_mut().load_font_data(FONT_BOLD.to_vec());
+
+    let tree = usvg::Tree::from_str(&card_svg(post, site_name), &options)
+        .map_err(|e| failed(format!("Failed to build social card: {}", e)))?;
+
+    let mut pixmap = tiny_skia::Pixmap::new(CARD_WIDTH, CARD_HEIGHT)
+        .ok_or_else(|| failed("Failed to allocate social card".to_string()))?;
+    resvg::render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
+
+    pixmap
+        .encode_png()
+        .map_err(|e| failed(format!("Failed to encode social card: {}", e)))
+}
+
+/// Absolute URL of a post's card, as meta tags need; requires `site_url`
+pub(crate) fn social_card_url(config: &BlogConfig, slug: &str) -> Result<String, AppError> {
+    canonical_url(config, slug)
+        .map(|post_url| format!("{}/{}", post_url, CARD_FILE_NAME))
+        .ok_or_else(|| AppError::invalid("Set the site URL in the settings to generate social cards"))
+}
+
+/// Render a post's card and stage it as `static/blog/<slug>/og.png`, where
+/// it is served next to the post itself
+pub(crate) fn stage_social_card(
+    transaction: &mut Transaction,
+    repo_path: &str,
+    post: &PostContent,
+    config: &BlogConfig,
+) -> Result<(), AppError> {
+    let png = render_social_card(post, &config.site_name)?;
+    let dir = asset_dir(repo_path, &post.slug, AssetLocation::Static);
+    transaction.write(dir.join(CARD_FILE_NAME), png);
+    Ok(())
+}
+
+/// Generate the Open Graph / Twitter card image for a post and reference it
+/// from the post's metadata. Returns the image URL.
+#[tauri::command]
+pub fn generate_social_card(repo_path: String, slug: String) -> Result<String, AppError> {
+    let config = load_config(&repo_path)?;
+    let post = read_post(repo_path.clone(), slug.clone())?;
+
+    let url = social_card_url(&config, &slug)?;
+    let mut transaction = Transaction::default();
+    stage_social_card(&mut transaction, &repo_path, &post, &config)?;
+    set_post_image(&mut transaction, &repo_path, &slug, &url)?;
+    transaction.commit()?;
+    Ok(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn card_urls_need_the_site_url() {
+        let mut config = BlogConfig::default();
+        assert_eq!(social_card_url(&config, "a").unwrap_err().code(), "invalid_input");
+
+        config.site_url = "https://example.com/".into();
+        assert_eq!(social_card_url(&config, "a").unwrap(), "https://example.com/blog/a/og.png");
+    }
+}
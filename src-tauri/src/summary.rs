@@ -0,0 +1,225 @@
+use crate::blog::{blog_dir, parse_post};
+use crate::error::AppError;
+use crate::markdown::{is_stopword, parser_options, plain_text, tokenize};
+use pulldown_cmark::{Event, Parser, Tag, TagEnd};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::sync::{Mutex, OnceLock};
+use std::time::SystemTime;
+
+/// Plain text of the first paragraph that has any prose in it
+fn first_paragraph(markdown: &str) -> String {
+    let mut paragraph = String::new();
+    let mut in_paragraph = false;
+
+    for event in Parser::new_ext(markdown, parser_options()) {
+        match event {
+            Event::Start(Tag::Paragraph) => in_paragraph = true,
+            Event::End(TagEnd::Paragraph) => {
+                in_paragraph = false;
+                if !paragraph.trim().is_empty() {
+                    break;
+                }
+                paragraph.clear();
+            }
+            Event::Text(t) | Event::Code(t) if in_paragraph => paragraph.push_str(&t),
+            Event::SoftBreak | Event::HardBreak if in_paragraph => paragraph.push(' '),
+            _ => {}
+        }
+    }
+
+    paragraph.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Shorten `text` to at most `max_length` characters, preferring to end on a
+/// full sentence and otherwise cutting on a word boundary with an ellipsis
+fn truncate_at_sentence(text: &str, max_length: usize) -> String {
+    if text.chars().count() <= max_length {
+        return text.to_string();
+    }
+
+    let cut = text
+        .char_indices()
+        .nth(max_length)
+        .map(|(i, _)| i)
+        .unwrap_or(text.len());
+    let window = &text[..cut];
+
+    let sentence_end = window
+        .char_indices()
+        .filter(|&(i, c)| {
+            matches!(c, '.' | '!' | '?') && text[i + c.len_utf8()..].starts_with(' ')
+        })
+        .map(|(i, c)| i + c.len_utf8())
+        .next_back();
+
+    match sentence_end {
+        Some(end) => window[..end].to_string(),
+        None => {
+            let end = window.rfind(' ').unwrap_or(window.len());
+            let trimmed = window[..end].trim_end_matches(|c: char| !c.is_alphanumeric());
+            format!("{}…", trimmed)
+        }
+    }
+}
+
+/// Derive an excerpt from the first paragraph of a Markdown body
+pub(crate) fn derive_excerpt(markdown: &str, max_length: usize) -> String {
+    truncate_at_sentence(&first_paragraph(markdown), max_length)
+}
+
+pub(crate) fn is_keyword_candidate(word: &str) -> bool {
+    word.chars().count() >= 3
+        && !is_stopword(word)
+        && !word.chars().all(|c| c.is_numeric())
+}
+
+/// Pick the `count` most distinctive terms of a post by TF-IDF, using the
+/// other posts' body terms as the reference corpus
+pub(crate) fn extract_keywords(
+    title: &str,
+    content: &str,
+    corpus_terms: &[&HashSet<String>],
+    count: usize,
+) -> String {
+    let words = tokenize(&format!("{}\n{}", title, plain_text(content)));
+
+    let mut term_freqs: HashMap<&str, usize> = HashMap::new();
+    for word in words.iter().filter(|w| is_keyword_candidate(w)) {
+        *term_freqs.entry(word.as_str()).or_insert(0) += 1;
+    }
+
+    let doc_count = corpus_terms.len() as f64;
+
+    let mut scored: Vec<(f64, &str)> = term_freqs
+        .into_iter()
+        .map(|(term, tf)| {
+            let df = corpus_terms.iter().filter(|terms| terms.contains(term)).count() as f64;
+            let idf = ((doc_count + 1.0) / (df + 1.0)).ln() + 1.0;
+            (tf as f64 * idf, term)
+        })
+        .collect();
+
+    scored.sort_by(|(a_score, a), (b_score, b)| b_score.total_cmp(a_score).then_with(|| a.cmp(b)));
+
+    scored
+        .into_iter()
+        .take(count)
+        .map(|(_, term)| term)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The terms of every post's body, by slug. Like the search index, a post is
+/// only re-read when its `+page.md` modification time or size changes, so
+/// the live preview can generate keywords on every keystroke.
+#[derive(Default)]
+struct KeywordCorpus {
+    repo_path: String,
+    docs: HashMap<String, ((SystemTime, u64), HashSet<String>)>,
+}
+
+fn keyword_corpus() -> &'static Mutex<KeywordCorpus> {
+    static CORPUS: OnceLock<Mutex<KeywordCorpus>> = OnceLock::new();
+    CORPUS.get_or_init(Default::default)
+}
+
+fn refresh_corpus(corpus: &mut KeywordCorpus, repo_path: &str) -> Result<(), AppError> {
+    if corpus.repo_path != repo_path {
+        corpus.docs.clear();
+        corpus.repo_path = repo_path.to_string();
+    }
+
+    let blog = blog_dir(repo_path);
+    let mut seen = HashSet::new();
+    let entries = match fs::read_dir(&blog) {
+        Ok(entries) => entries,
+        // No blog directory yet means no other posts
+        Err(_) if !blog.exists() => {
+            corpus.docs.clear();
+            return Ok(());
+        }
+        Err(e) => return Err(AppError::io("list posts", &blog, e)),
+    };
+
+    for entry in entries {
+        let entry = entry.map_err(|e| AppError::io("list posts", &blog, e))?;
+        let slug = entry.file_name().to_string_lossy().to_string();
+        if slug.starts_with('.') {
+            continue;
+        }
+
+        let post_file = entry.path().join("+page.md");
+        let Ok(file_meta) = fs::metadata(&post_file) else {
+            continue;
+        };
+        let stamp = (
+            file_meta.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+            file_meta.len(),
+        );
+        seen.insert(slug.clone());
+
+        if corpus.docs.get(&slug).is_some_and(|(s, _)| *s == stamp) {
+            continue;
+        }
+
+        let file_content = fs::read_to_string(&post_file)
+            .map_err(|e| AppError::io("read post", &post_file, e))?;
+        let terms = tokenize(&plain_text(&parse_post(slug.clone(), &file_content).content))
+            .into_iter()
+            .collect();
+        corpus.docs.insert(slug, (stamp, terms));
+    }
+
+    corpus.docs.retain(|slug, _| seen.contains(slug));
+    Ok(())
+}
+
+/// Generate keywords for a post against every other post in the repo
+pub(crate) fn generate_keywords(
+    repo_path: &str,
+    slug: &str,
+    title: &str,
+    content: &str,
+    count: usize,
+) -> Result<String, AppError> {
+    let mut corpus = keyword_corpus().lock().map_err(|e| AppError::Other { message: e.to_string() })?;
+    refresh_corpus(&mut corpus, repo_path)?;
+
+    let others: Vec<&HashSet<String>> = corpus
+        .docs
+        .iter()
+        .filter(|(other, _)| other.as_str() != slug)
+        .map(|(_, (_, terms))| terms)
+        .collect();
+    Ok(extract_keywords(title, content, &others, count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blog::tests::{create, fake_repo};
+
+    #[test]
+    fn keyword_corpus_follows_the_files() {
+        let repo = fake_repo("keyword-corpus");
+        create(&repo, "a", "A", "Ownership and borrowing");
+        create(&repo, "b", "B", "Lifetimes");
+
+        let mut corpus = KeywordCorpus::default();
+        refresh_corpus(&mut corpus, &repo).unwrap();
+        assert!(corpus.docs["a"].1.contains("ownership"));
+        assert!(corpus.docs["b"].1.contains("lifetimes"));
+
+        // Edited outside the app, and deleted
+        let post_file = blog_dir(&repo).join("a").join("+page.md");
+        let edited = fs::read_to_string(&post_file).unwrap().replace("Ownership and borrowing", "Traits");
+        fs::write(&post_file, edited).unwrap();
+        fs::remove_dir_all(blog_dir(&repo).join("b")).unwrap();
+
+        refresh_corpus(&mut corpus, &repo).unwrap();
+        assert!(corpus.docs["a"].1.contains("traits"));
+        assert!(!corpus.docs["a"].1.contains("ownership"));
+        assert!(!corpus.docs.contains_key("b"));
+    }
+}
@@ -0,0 +1,131 @@
+use crate::markdown::parser_options;
+use pulldown_cmark::{Event, Parser, Tag};
+use regex::Regex;
+use std::ops::Range;
+
+/// Length of a brace entity at the start of `text` (`&#123;` or `&#125;`,
+/// with any number of `amp;` after the `&`), and how many `amp;` it has.
+///
+/// Braces are escaped to the plain entities, and every entity already in the
+/// text gains one more `amp;`, so unescaping (which removes one, or turns a
+/// plain entity back into a brace) gives back exactly what was written.
+fn brace_entity(text: &str) -> Option<(usize, usize)> {
+    let mut rest = text.strip_prefix('&')?;
+    let mut amps = 0;
+    while let Some(after) = rest.strip_prefix("amp;") {
+        rest = after;
+        amps += 1;
+    }
+    if rest.starts_with("#123;") || rest.starts_with("#125;") {
+        Some((text.len() - rest.len() + "#123;".len(), amps))
+    } else {
+        None
+    }
+}
+
+/// Byte ranges the Svelte compiler must see untouched: fenced/indented code
+/// (mdsvex escapes those itself), inline code spans, and component tags such
+/// as `<Citation key={...} />` or `<svelte:head>`
+fn protected_ranges(content: &str) -> Vec<Range<usize>> {
+    let component_re =
+        Regex::new(r"</?(?:[A-Z][\w.]*|svelte:[\w-]+)(?:\s[^>]*)?/?>").unwrap();
+
+    let mut ranges: Vec<Range<usize>> = Parser::new_ext(content, parser_options())
+        .into_offset_iter()
+        .filter_map(|(event, range)| match event {
+            Event::Start(Tag::CodeBlock(_)) | Event::Code(_) => Some(range),
+            _ => None,
+        })
+        .chain(component_re.find_iter(content).map(|m| m.range()))
+        .collect();
+
+    ranges.sort_by_key(|r| r.start);
+    ranges
+}
+
+/// Run `replace` at every position of `content` outside the protected ranges.
+/// It returns how many bytes it consumed and what to put instead, or `None`
+/// to keep the character.
+fn replace_outside_protected(content: &str, replace: impl Fn(&str) -> Option<(usize, String)>) -> String {
+    let ranges = protected_ranges(content);
+    let mut result = String::with_capacity(content.len());
+    let mut i = 0;
+    let mut next_range = 0;
+
+    while i < content.len() {
+        while next_range < ranges.len() && ranges[next_range].end <= i {
+            next_range += 1;
+        }
+        if let Some(range) = ranges.get(next_range).filter(|r| r.start <= i) {
+            result.push_str(&content[i..range.end]);
+            i = range.end;
+            continue;
+        }
+
+        let rest = &content[i..];
+        if let Some((len, replacement)) = replace(rest) {
+            result.push_str(&replacement);
+            i += len;
+        } else {
+            let c = rest.chars().next().unwrap();
+            result.push(c);
+            i += c.len_utf8();
+        }
+    }
+
+    result
+}
+
+/// Escape characters the Svelte compiler would treat as template syntax
+/// (`{` and `}`) everywhere except code and component tags
+pub(crate) fn escape_svelte(content: &str) -> String {
+    replace_outside_protected(content, |rest| {
+        if rest.starts_with('{') {
+            Some((1, "&#123;".to_string()))
+        } else if rest.starts_with('}') {
+            Some((1, "&#125;".to_string()))
+        } else {
+            brace_entity(rest).map(|(len, _)| (len, format!("&amp;{}", &rest[1..len])))
+        }
+    })
+}
+
+/// Reverse `escape_svelte`, giving back the Markdown as it was written
+pub(crate) fn unescape_svelte(content: &str) -> String {
+    replace_outside_protected(content, |rest| match brace_entity(rest)? {
+        (len, 0) if rest.starts_with("&#123;") => Some((len, "{".to_string())),
+        (len, 0) => Some((len, "}".to_string())),
+        (len, _) => Some((len, format!("&{}", &rest["&amp;".len()..len]))),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escaping_round_trips() {
+        for text in ["{", "}", "<", "&", "&#123;", "&#125;", "&amp;#123;", "&amp;amp;#125;", "{&#123;&amp;#123;}"] {
+            let escaped = escape_svelte(text);
+            assert!(!escaped.contains(['{', '}']), "{} -> {}", text, escaped);
+            assert_eq!(unescape_svelte(&escaped), text, "{} -> {}", text, escaped);
+        }
+    }
+
+    #[test]
+    fn escaped_text_renders_as_written() {
+        assert_eq!(escape_svelte("a {b}"), "a &#123;b&#125;");
+        // Rendered, `&amp;#123;` shows as the `&#123;` that was written
+        assert_eq!(escape_svelte("&#123;"), "&amp;#123;");
+        assert_eq!(escape_svelte("&amp;#123;"), "&amp;amp;#123;");
+    }
+
+    #[test]
+    fn code_and_components_are_left_alone() {
+        let text = "`{code}` and <Citation key={refs.a} /> then {b}\n\n```\n{&#123;}\n```\n";
+        let escaped = escape_svelte(text);
+        assert!(escaped.contains("`{code}` and <Citation key={refs.a} /> then &#123;b&#125;"));
+        assert!(escaped.contains("{&#123;}"));
+        assert_eq!(unescape_svelte(&escaped), text);
+    }
+}
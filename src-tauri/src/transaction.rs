@@ -0,0 +1,220 @@
+use crate::error::AppError;
+use crate::watcher::{expect_removal, expect_write};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A group of file writes that land together or not at all.
+///
+/// Writes are only collected until `commit`, which stages every file next to
+/// its target, then moves them into place with renames. If anything fails,
+/// files already replaced are restored and new files and directories removed.
+#[derive(Default)]
+pub(crate) struct Transaction {
+    writes: Vec<(PathBuf, Vec<u8>)>,
+}
+
+/// A target that has been replaced, and where its previous content is kept
+struct Applied {
+    target: PathBuf,
+    backup: Option<PathBuf>,
+}
+
+/// Hidden sibling of `path`, e.g. `+page.md` -> `.+page.md.1234.tmp`
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!(".{}.{}.{}", name, std::process::id(), suffix))
+}
+
+/// Create the missing ancestors of `dir`, recording them outermost first
+fn create_dirs(dir: &Path, created: &mut Vec<PathBuf>) -> Result<(), AppError> {
+    let missing: Vec<PathBuf> = dir
+        .ancestors()
+        .take_while(|d| !d.as_os_str().is_empty() && !d.exists())
+        .map(Path::to_path_buf)
+        .collect();
+
+    for dir in missing.into_iter().rev() {
+        fs::create_dir(&dir).map_err(|e| AppError::io("create directory", &dir, e))?;
+        created.push(dir);
+    }
+    Ok(())
+}
+
+impl Transaction {
+    /// Queue `content` to be written to `path`, replacing any earlier write
+    /// to the same path in this transaction
+    pub(crate) fn write(&mut self, path: impl Into<PathBuf>, content: impl Into<Vec<u8>>) {
+        let path = path.into();
+        let content = content.into();
+        match self.writes.iter_mut().find(|(p, _)| *p == path) {
+            Some(write) => write.1 = content,
+            None => self.writes.push((path, content)),
+        }
+    }
+
+    /// Write every queued file, or none of them
+    pub(crate) fn commit(self) -> Result<(), AppError> {
+        let mut created = Vec::new();
+        let mut staged = Vec::new();
+
+        // Stage everything first so that a full disk or a bad path fails
+        // before any target has been touched
+        for (target, content) in &self.writes {
+            let temp = sibling(target, "tmp");
+            let result = match target.parent() {
+                Some(parent) => create_dirs(parent, &mut created),
+                None => Ok(()),
+            }
+            .and_then(|_| fs::write(&temp, content).map_err(|e| AppError::io("write", target, e)));
+
+            if let Err(e) = result {
+                let _ = fs::remove_file(&temp);
+                rollback(&[], &staged, &created);
+                return Err(e);
+            }
+            staged.push((temp, target.clone()));
+        }
+
+        let mut applied: Vec<Applied> = Vec::new();
+        for (i, ((temp, target), (_, content))) in staged.iter().zip(&self.writes).enumerate() {
+            let backup = target.exists().then(|| sibling(target, "bak"));
+            let result = match &backup {
+                Some(backup) => fs::rename(target, backup),
+                None => Ok(()),
+            }
+            .map_err(|e| AppError::io("replace", target, e))
+            .and_then(|_| {
+                applied.push(Applied {
+                    target: target.clone(),
+                    backup: backup.clone(),
+                });
+                expect_write(target, content);
+                fs::rename(temp, target).map_err(|e| AppError::io("write", target, e))
+            });
+
+            if let Err(e) = result {
+                rollback(&applied, &staged[i..], &created);
+                return Err(e);
+            }
+        }
+
+        for backup in applied.iter().filter_map(|a| a.backup.as_ref()) {
+            if let Err(e) = fs::remove_file(backup) {
+                log::warn!("Failed to remove backup {}: {}", backup.display(), e);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Undo a partly applied commit: put back replaced files, delete new ones,
+/// remove leftover staged files and the directories the commit created
+fn rollback(applied: &[Applied], staged: &[(PathBuf, PathBuf)], created: &[PathBuf]) {
+    for applied in applied.iter().rev() {
+        let result = match &applied.backup {
+            Some(backup) => {
+                if let Ok(content) = fs::read(backup) {
+                    expect_write(&applied.target, &content);
+                }
+                fs::rename(backup, &applied.target)
+            }
+            None => {
+                expect_removal(&applied.target);
+                fs::remove_file(&applied.target)
+            }
+        };
+        if let Err(e) = result {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                log::error!("Failed to roll back {}: {}", applied.target.display(), e);
+            }
+        }
+    }
+
+    for (temp, _) in staged {
+        let _ = fs::remove_file(temp);
+    }
+
+    // Innermost first; anything that is not empty is left alone
+    for dir in created.iter().rev() {
+        let _ = fs::remove_dir(dir);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("blog-manager-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn entries(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn commit_writes_every_file() {
+        let dir = temp_dir("transaction-commit");
+        fs::write(dir.join("a.txt"), "old").unwrap();
+
+        let mut transaction = Transaction::default();
+        transaction.write(dir.join("a.txt"), "first");
+        transaction.write(dir.join("new/deep/b.txt"), "b");
+        transaction.write(dir.join("a.txt"), "new");
+        transaction.commit().unwrap();
+
+        assert_eq!(fs::read_to_string(dir.join("a.txt")).unwrap(), "new");
+        assert_eq!(fs::read_to_string(dir.join("new/deep/b.txt")).unwrap(), "b");
+        assert_eq!(entries(&dir), ["a.txt", "new"]);
+    }
+
+    #[test]
+    fn failed_staging_touches_nothing() {
+        let dir = temp_dir("transaction-staging");
+        fs::write(dir.join("a.txt"), "old").unwrap();
+
+        let mut transaction = Transaction::default();
+        transaction.write(dir.join("a.txt"), "new");
+        transaction.write(dir.join("fresh/c.txt"), "c");
+        // A file cannot be a directory
+        transaction.write(dir.join("a.txt/inner"), "boom");
+        assert!(transaction.commit().is_err());
+
+        assert_eq!(fs::read_to_string(dir.join("a.txt")).unwrap(), "old");
+        assert_eq!(entries(&dir), ["a.txt"]);
+    }
+
+    #[test]
+    fn failed_rename_rolls_back() {
+        let dir = temp_dir("transaction-rename");
+        fs::write(dir.join("a.txt"), "old").unwrap();
+        // A non-empty directory can neither be replaced by a file nor moved
+        // onto the non-empty directory where its backup would go
+        let blocked = dir.join("blocked");
+        fs::create_dir_all(blocked.join("inside")).unwrap();
+        let backup = sibling(&blocked, "bak");
+        fs::create_dir_all(backup.join("inside")).unwrap();
+
+        let mut transaction = Transaction::default();
+        transaction.write(dir.join("a.txt"), "new");
+        transaction.write(dir.join("fresh/c.txt"), "c");
+        transaction.write(&blocked, "file");
+        let error = transaction.commit().unwrap_err();
+
+        assert!(matches!(&error, AppError::Io { path, .. } if *path == blocked), "{:?}", error);
+        assert_eq!(fs::read_to_string(dir.join("a.txt")).unwrap(), "old");
+        assert!(blocked.join("inside").is_dir());
+        let mut expected = vec!["a.txt".to_string(), "blocked".to_string()];
+        expected.push(backup.file_name().unwrap().to_string_lossy().to_string());
+        expected.sort();
+        assert_eq!(entries(&dir), expected);
+    }
+}
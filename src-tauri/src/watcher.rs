@@ -0,0 +1,270 @@
+use crate::assets::hash_bytes;
+use crate::blog::blog_dir;
+use crate::error::AppError;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::sync::{Mutex, MutexGuard, OnceLock, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, State};
+
+/// Quiet period before a burst of file system events is reported
+const SETTLE: Duration = Duration::from_millis(300);
+/// How long an own write waits for its event before it is forgotten
+const OWN_WRITE_TTL: Duration = Duration::from_secs(10);
+
+/// Payload of the `post-changed`, `post-added`, `post-removed` and
+/// `bib-changed` events
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FileChange {
+    /// Post the change belongs to; `None` for the listing and the bib file
+    pub slug: Option<String>,
+    /// Path relative to the repo root
+    pub path: String,
+}
+
+/// The active watcher, replaced whenever the UI switches repo
+#[derive(Default)]
+pub struct RepoWatcher {
+    watcher: Mutex<Option<RecommendedWatcher>>,
+}
+
+/// What a file the app just wrote, moved or removed should now look like
+struct OwnWrite {
+    /// Hash of the content written; `None` once the file is gone
+    hash: Option<String>,
+    expires: Instant,
+}
+
+/// Files the app itself just changed, so its own saves and deletions are not
+/// reported back as outside edits. Expired entries are dropped; the map stays
+/// usable even if a thread panicked while holding it.
+fn own_writes() -> MutexGuard<'static, HashMap<PathBuf, OwnWrite>> {
+    static OWN_WRITES: OnceLock<Mutex<HashMap<PathBuf, OwnWrite>>> = OnceLock::new();
+    let mut own = OWN_WRITES
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    let now = Instant::now();
+    own.retain(|_, write| write.expires > now);
+    own
+}
+
+/// Event paths are canonical; the file itself may not exist yet
+fn canonical(path: &Path) -> PathBuf {
+    match (path.parent().and_then(|p| fs::canonicalize(p).ok()), path.file_name()) {
+        (Some(parent), Some(name)) => parent.join(name),
+        _ => path.to_path_buf(),
+    }
+}
+
+fn expect(path: &Path, hash: Option<String>) {
+    let expires = Instant::now() + OWN_WRITE_TTL;
+    own_writes().insert(canonical(path), OwnWrite { hash, expires });
+}
+
+/// Record that the app is about to write `content` to `path`
+pub(crate) fn expect_write(path: &Path, content: &[u8]) {
+    expect(path, Some(hash_bytes(content)));
+}
+
+/// Record that the app is about to remove `path`, or move it out of the way
+pub(crate) fn expect_removal(path: &Path) {
+    expect(path, None);
+}
+
+/// Whether the file is now exactly as the app last left it
+fn is_own_write(path: &Path) -> bool {
+    let mut own = own_writes();
+    let matches = match own.get(path).map(|write| &write.hash) {
+        None => return false,
+        Some(Some(expected)) => fs::read(path).is_ok_and(|content| &hash_bytes(&content) == expected),
+        Some(None) => !path.exists(),
+    };
+    if matches {
+        own.remove(path);
+    }
+    matches
+}
+
+fn bib_path(repo_path: &Path) -> PathBuf {
+    repo_path.join("src").join("lib").join("references.bib")
+}
+
+/// Slugs of the posts currently on disk
+fn existing_slugs(blog: &Path) -> BTreeSet<String> {
+    let Ok(entries) = fs::read_dir(blog) else {
+        return BTreeSet::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| entry.path().join("+page.md").is_file())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect()
+}
+
+struct Tracker {
+    repo: PathBuf,
+    blog: PathBuf,
+    bib: PathBuf,
+    posts: BTreeSet<String>,
+}
+
+impl Tracker {
+    fn relative(&self, path: &Path) -> String {
+        path.strip_prefix(&self.repo)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/")
+    }
+
+    /// Work out which events a set of touched paths amounts to. Posts are
+    /// compared against the known set rather than trusting event kinds,
+    /// which differ between platforms and editors' save strategies.
+    fn changes(&mut self, paths: BTreeSet<PathBuf>) -> Vec<(&'static str, FileChange)> {
+        let mut changes = Vec::new();
+        let mut slugs = BTreeSet::new();
+
+        for path in paths {
+            // Staged and backup files from our own atomic writes
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            if name.starts_with('.') {
+                continue;
+            }
+
+            if path == self.bib {
+                if !is_own_write(&path) {
+                    changes.push(("bib-changed", FileChange { slug: None, path: self.relative(&path) }));
+                }
+                continue;
+            }
+
+            if path == self.blog.join("+page.svelte") {
+                if !is_own_write(&path) {
+                    changes.push(("post-changed", FileChange { slug: None, path: self.relative(&path) }));
+                }
+                continue;
+            }
+
+            let Ok(inside) = path.strip_prefix(&self.blog) else {
+                continue;
+            };
+            let mut parts = inside.components();
+            match (parts.next(), parts.next(), parts.next()) {
+                // The post directory itself, or its page (not its assets)
+                (Some(slug), None, _) => {
+                    slugs.insert(slug.as_os_str().to_string_lossy().to_string());
+                }
+                (Some(slug), Some(page), None) if page.as_os_str() == "+page.md" => {
+                    slugs.insert(slug.as_os_str().to_string_lossy().to_string());
+                }
+                _ => {}
+            }
+        }
+
+        for slug in slugs {
+            let page = self.blog.join(&slug).join("+page.md");
+            let change = FileChange {
+                path: self.relative(&page),
+                slug: Some(slug.clone()),
+            };
+
+            let event = match (page.is_file(), self.posts.contains(&slug)) {
+                (true, false) => {
+                    self.posts.insert(slug);
+                    "post-added"
+                }
+                (false, true) => {
+                    self.posts.remove(&slug);
+                    "post-removed"
+                }
+                (true, true) => "post-changed",
+                (false, false) => continue,
+            };
+
+            if !is_own_write(&page) {
+                changes.push((event, change));
+            }
+        }
+        changes
+    }
+}
+
+/// Watch a repo's posts, listing and bibliography, emitting an event for each
+/// change made outside the app. Replaces any previously watched repo.
+#[tauri::command]
+pub fn watch_repo(app: AppHandle, state: State<'_, RepoWatcher>, repo_path: String) -> Result<(), AppError> {
+    let repo = fs::canonicalize(&repo_path).map_err(|e| AppError::io("open repo", Path::new(&repo_path), e))?;
+    let blog = blog_dir(&repo.to_string_lossy());
+    if !blog.is_dir() {
+        return Err(AppError::BlogDirNotFound { path: blog });
+    }
+
+    let (tx, rx) = channel();
+    let mut watcher = notify::recommended_watcher(move |result: notify::Result<notify::Event>| {
+        match result {
+            Ok(event) => {
+                for path in event.paths {
+                    let _ = tx.send(path);
+                }
+            }
+            Err(e) => log::warn!("File watcher error: {}", e),
+        }
+    })
+    .map_err(|e| AppError::Other {
+        message: format!("Failed to start file watcher: {}", e),
+    })?;
+
+    watcher
+        .watch(&blog, RecursiveMode::Recursive)
+        .map_err(|e| AppError::Other {
+            message: format!("Failed to watch blog directory: {}", e),
+        })?;
+
+    // Watch the directory rather than the file, so editors that save by
+    // replacing the file keep being picked up
+    let bib = bib_path(&repo);
+    if let Some(lib_dir) = bib.parent().filter(|d| d.is_dir()) {
+        watcher
+            .watch(lib_dir, RecursiveMode::NonRecursive)
+            .map_err(|e| AppError::Other {
+                message: format!("Failed to watch references: {}", e),
+            })?;
+    }
+
+    let mut tracker = Tracker {
+        posts: existing_slugs(&blog),
+        repo,
+        blog,
+        bib,
+    };
+
+    // Ends when the watcher, and with it the sender, is dropped
+    thread::spawn(move || {
+        while let Ok(first) = rx.recv() {
+            let mut paths = BTreeSet::from([first]);
+            loop {
+                match rx.recv_timeout(SETTLE) {
+                    Ok(path) => {
+                        paths.insert(path);
+                    }
+                    Err(RecvTimeoutError::Timeout) => break,
+                    Err(RecvTimeoutError::Disconnected) => return,
+                }
+            }
+
+            for (event, change) in tracker.changes(paths) {
+                if let Err(e) = app.emit(event, change) {
+                    log::warn!("Failed to emit {}: {}", event, e);
+                }
+            }
+        }
+    });
+
+    *state.watcher.lock().unwrap() = Some(watcher);
+    Ok(())
+}
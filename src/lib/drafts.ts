@@ -0,0 +1,62 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export interface Draft {
+    /** Slug of the post being edited; null for the new post form */
+    editing: string | null;
+    slug: string;
+    title: string;
+    excerpt: string;
+    content: string;
+    tags: string[];
+    keywords: string;
+    saved_at?: number;
+}
+
+export interface RecoverableDraft {
+    draft: Draft;
+    age_secs: number;
+    diff: { lines_added: number; lines_removed: number; fields: { field: string }[] } | null;
+}
+
+/** Queue the buffer for a (throttled) autosave; failures are only logged */
+export function autosaveDraft(repoPath: string, draft: Draft) {
+    invoke("autosave_draft", { repoPath, draft }).catch((err) =>
+        console.error("Autosave failed:", err),
+    );
+}
+
+/** Write queued drafts now rather than when the throttle allows */
+export function flushDrafts() {
+    invoke("flush_drafts").catch((err) => console.error("Autosave failed:", err));
+}
+
+/**
+ * Flush drafts whenever the window loses focus or closes, and once more when
+ * the editor goes away. Returns the cleanup, for use in an `$effect`.
+ */
+export function flushDraftsOnLeave(): () => void {
+    window.addEventListener("blur", flushDrafts);
+    window.addEventListener("beforeunload", flushDrafts);
+    return () => {
+        window.removeEventListener("blur", flushDrafts);
+        window.removeEventListener("beforeunload", flushDrafts);
+        flushDrafts();
+    };
+}
+
+export function discardDraft(repoPath: string, editing: string | null) {
+    return invoke("discard_draft", { repoPath, editing });
+}
+
+export function recoverDrafts(repoPath: string) {
+    return invoke<RecoverableDraft[]>("recover_drafts", { repoPath });
+}
+
+export function formatAge(seconds: number): string {
+    if (seconds < 60) return "less than a minute ago";
+    const minutes = Math.floor(seconds / 60);
+    if (minutes < 60) return `${minutes} min ago`;
+    const hours = Math.floor(minutes / 60);
+    if (hours < 24) return `${hours} h ago`;
+    return `${Math.floor(hours / 24)} days ago`;
+}
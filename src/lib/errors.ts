@@ -0,0 +1,37 @@
+/** Stable codes of the errors returned by the app's commands */
+export type ErrorCode =
+    | "post_exists"
+    | "post_not_found"
+    | "blog_dir_not_found"
+    | "listing_marker_missing"
+    | "bib_not_found"
+    | "invalid_post"
+    | "invalid_config"
+    | "invalid_input"
+    | "conflict"
+    | "io"
+    | "git"
+    | "other";
+
+export interface AppError {
+    code: ErrorCode;
+    /** Ready to show to the user */
+    message: string;
+    /** File or directory the error is about, if any */
+    path: string | null;
+    slug?: string;
+}
+
+export function isAppError(err: unknown): err is AppError {
+    return (
+        typeof err === "object" &&
+        err !== null &&
+        "code" in err &&
+        "message" in err
+    );
+}
+
+/** Message for any error a command rejected with */
+export function errorMessage(err: unknown): string {
+    return isAppError(err) ? err.message : String(err);
+}
@@ -0,0 +1,33 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export interface PostPreview {
+    /** The `+page.md` that saving would write */
+    template: string;
+    /** The Markdown body rendered to HTML */
+    html: string;
+}
+
+export interface PreviewInput {
+    slug: string;
+    title: string;
+    excerpt: string;
+    content: string;
+    tags: string[];
+    keywords: string;
+}
+
+/**
+ * Render a post with the same code that writes it. With `newPost`, a slug
+ * that is already taken rejects with a `post_exists` error.
+ */
+export function renderPreview(
+    repoPath: string,
+    post: PreviewInput,
+    newPost = false,
+) {
+    return invoke<PostPreview>("render_preview", {
+        repoPath,
+        ...post,
+        newPost,
+    });
+}
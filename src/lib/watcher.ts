@@ -0,0 +1,32 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+
+export type ChangeEvent =
+    | "post-changed"
+    | "post-added"
+    | "post-removed"
+    | "bib-changed";
+
+export interface FileChange {
+    /** Post the change belongs to; null for the listing and the bib file */
+    slug: string | null;
+    path: string;
+}
+
+/** Start reporting outside edits to the repo (replaces any earlier watch) */
+export function watchRepo(repoPath: string) {
+    return invoke("watch_repo", { repoPath });
+}
+
+/** Listen to several change events at once; resolves to a single unlisten */
+export async function onRepoChange(
+    events: ChangeEvent[],
+    handler: (event: ChangeEvent, change: FileChange) => void,
+): Promise<UnlistenFn> {
+    const unlisteners = await Promise.all(
+        events.map((event) =>
+            listen<FileChange>(event, (e) => handler(event, e.payload)),
+        ),
+    );
+    return () => unlisteners.forEach((unlisten) => unlisten());
+}